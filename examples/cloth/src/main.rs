@@ -23,6 +23,7 @@ use std::{
 };
 
 use miniphys::cloth::Cloth;
+use miniphys::Real;
 
 enum Event<I> {
     Input(I),
@@ -94,7 +95,7 @@ fn main() -> Result<(), Box<dyn Error>> {
                     ..
                 } => {
                     println!("Got mouse event");
-                    mouse_pos = Vector2::new(column as f64, row as f64);
+                    mouse_pos = Vector2::new(column as Real, row as Real);
                     if button == MouseButton::Right {
                         // Start dragging particles
                         dragging = true;
@@ -122,7 +123,7 @@ fn main() -> Result<(), Box<dyn Error>> {
                     row,
                     ..
                 } => {
-                    mouse_pos = Vector2::new(column as f64, row as f64);
+                    mouse_pos = Vector2::new(column as Real, row as Real);
                     if dragging && right_button {
                         cloth.move_selected_particles(mouse_pos);
                     }
@@ -154,10 +155,10 @@ fn ui(f: &mut Frame, cloth: &Cloth) {
                 let p1 = cloth.particles()[index_a].position();
                 let p2 = cloth.particles()[index_b].position();
                 ctx.draw(&Line {
-                    x1: p1.x,
-                    y1: p1.y,
-                    x2: p2.x,
-                    y2: p2.y,
+                    x1: p1.x.into(),
+                    y1: p1.y.into(),
+                    x2: p2.x.into(),
+                    y2: p2.y.into(),
                     color: Color::White,
                 });
             }
@@ -171,8 +172,8 @@ fn ui(f: &mut Frame, cloth: &Cloth) {
                     Color::Yellow
                 };
                 let circle = Circle {
-                    x: pos.x,
-                    y: pos.y,
+                    x: pos.x.into(),
+                    y: pos.y.into(),
                     radius: 0.5,
                     color,
                 };
@@ -1,5 +1,6 @@
 use crossterm::event::{self, Event, KeyCode};
-use miniphys::projectile_motion::Projectile;
+use miniphys::projectile_motion::{Projectile, Surface};
+use nalgebra::Vector2;
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Style},
@@ -20,24 +21,33 @@ struct App {
 impl App {
     fn new() -> Self {
         // Initial values for demonstration
-        let initial_position = [0.0, 0.0];
-        let initial_velocity = [10.0, 30.0]; // Adjust these values as needed
-        let gravity = [0.0, -9.81]; // Gravity acts downward
+        let initial_position = Vector2::new(0.0, 0.0);
+        let initial_velocity = Vector2::new(10.0, 30.0); // Adjust these values as needed
+        let gravity = Vector2::new(0.0, -9.81); // Gravity acts downward
+
+        let mut projectile = Projectile::new(initial_position, initial_velocity, gravity);
+        projectile.add_surface(Surface::Ground(0.0));
+
+        // Predict the whole flight up front so the arc can be drawn ahead
+        let trajectory = projectile
+            .predict_trajectory(Duration::from_millis(50), Duration::from_secs(10))
+            .iter()
+            .map(|position| (position.x, position.y))
+            .collect();
 
         App {
-            projectile: Projectile::new(initial_position, initial_velocity, gravity),
-            trajectory: vec![initial_position.into()],
+            projectile,
+            trajectory,
             time: 0.0,
         }
     }
 
     fn update(&mut self, delta_time: f64) {
-        self.projectile.update(Duration::from_secs_f64(delta_time));
-        self.trajectory.push(self.projectile.position());
+        let impact = self.projectile.update(Duration::from_secs_f64(delta_time));
         self.time += delta_time;
 
-        // Reset if the projectile goes below the ground
-        if self.projectile.position().1 < 0.0 {
+        // Reset once the projectile hits the ground
+        if impact.is_some() {
             self.reset();
         }
     }
@@ -71,14 +81,14 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .x_bounds([0.0, 100.0])
                 .y_bounds([0.0, 100.0])
                 .paint(|ctx| {
-                    // Draw the trajectory
+                    // Draw the predicted trajectory
                     for &(x, y) in &app.trajectory {
                         ctx.print(x, y, ".");
                     }
 
                     // Draw the current position of the projectile
-                    let (x, y) = app.projectile.position();
-                    ctx.print(x, y, "O");
+                    let position = app.projectile.position();
+                    ctx.print(position.x, position.y, "O");
 
                     // Draw the ground
                     let line = Line::new(0.0, 0.0, 100.0, 0.0, Color::Green);
@@ -88,10 +98,10 @@ fn main() -> Result<(), Box<dyn Error>> {
             f.render_widget(canvas, chunks[0]);
 
             // Display time and position
-            let (x, y) = app.projectile.position();
+            let position = app.projectile.position();
             let info = Paragraph::new(format!(
                 "Time: {:.2}s | Position: ({:.2}, {:.2})",
-                app.time, x, y
+                app.time, position.x, position.y
             ))
             .style(Style::default().fg(Color::White));
             f.render_widget(info, chunks[1]);
@@ -0,0 +1,311 @@
+use nalgebra::SVector;
+
+use crate::spring::VectorSpring;
+use crate::Real;
+
+/// One part of an animation played by a [`Timeline`].
+pub enum Step<const D: usize = 2> {
+    /// Sets the target of one of the timeline's springs, then waits until the
+    /// spring has settled there. A spring with no damping never settles, so
+    /// this step never finishes and the timeline keeps playing until stopped.
+    To {
+        spring: usize,
+        target: SVector<Real, D>,
+    },
+    /// Waits for a number of seconds.
+    Delay(Real),
+    /// Runs a callback, taking no time.
+    Call(Box<dyn FnMut()>),
+    /// Plays steps one after another.
+    Sequence(Vec<Step<D>>),
+    /// Plays steps at the same time, finishing when all of them have.
+    Parallel(Vec<Step<D>>),
+}
+
+impl<const D: usize> Step<D> {
+    pub fn call<F: FnMut() + 'static>(callback: F) -> Self {
+        Step::Call(Box::new(callback))
+    }
+
+    /// Moves a spring through each target in turn, settling at every one.
+    pub fn waypoints<I>(spring: usize, targets: I) -> Self
+    where
+        I: IntoIterator<Item = SVector<Real, D>>,
+    {
+        Step::Sequence(
+            targets
+                .into_iter()
+                .map(|target| Step::To { spring, target })
+                .collect(),
+        )
+    }
+}
+
+/// Plays sequences of spring targets, delays and callbacks, all driven by a
+/// single [`Timeline::tick`].
+///
+/// The springs are advanced with their exact closed-form solution, and a
+/// [`Step::To`] lasts for the spring's analytic settle time. Every event
+/// lands at the same simulated time however the ticks are split up, so the
+/// animation doesn't depend on the frame rate.
+///
+/// ```rust
+/// use miniphys::animation::{Step, Timeline};
+/// use miniphys::spring::{fps, Spring2};
+/// use nalgebra::Vector2;
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// let mut timeline = Timeline::new(0.01);
+/// let button = timeline.add_spring(Spring2::new(fps(60), 12.0, 0.7, Vector2::zeros()));
+/// let label = timeline.add_spring(Spring2::new(fps(60), 8.0, 1.0, Vector2::zeros()));
+///
+/// let done = Rc::new(Cell::new(false));
+/// let flag = done.clone();
+/// timeline.on_complete(move || flag.set(true));
+/// timeline.play(Step::Sequence(vec![
+///     Step::waypoints(button, [Vector2::new(100.0, 0.0), Vector2::new(100.0, 50.0)]),
+///     Step::Delay(0.25),
+///     Step::Parallel(vec![
+///         Step::To { spring: button, target: Vector2::zeros() },
+///         Step::To { spring: label, target: Vector2::new(0.0, 20.0) },
+///     ]),
+/// ]));
+///
+/// while timeline.is_playing() {
+///     timeline.tick(fps(60));
+/// }
+/// assert!(done.get());
+/// ```
+pub struct Timeline<const D: usize = 2> {
+    springs: Vec<VectorSpring<D>>,
+    playing: Option<Node<D>>,
+    tolerance: Real, // Distance and speed at which a spring counts as settled
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl<const D: usize> Timeline<D> {
+    /// Creates an empty timeline. A spring counts as settled once it's within
+    /// `tolerance` of its target and moving slower than `tolerance`.
+    pub fn new(tolerance: Real) -> Self {
+        Timeline {
+            springs: Vec::new(),
+            playing: None,
+            tolerance,
+            on_complete: None,
+        }
+    }
+
+    /// Adds a spring for steps to animate and returns its index.
+    pub fn add_spring(&mut self, spring: VectorSpring<D>) -> usize {
+        self.springs.push(spring);
+        self.springs.len() - 1
+    }
+
+    pub fn spring(&self, index: usize) -> &VectorSpring<D> {
+        &self.springs[index]
+    }
+
+    pub fn spring_mut(&mut self, index: usize) -> &mut VectorSpring<D> {
+        &mut self.springs[index]
+    }
+
+    pub fn springs(&self) -> &[VectorSpring<D>] {
+        &self.springs
+    }
+
+    /// Sets a callback to run each time a played animation finishes.
+    pub fn on_complete<F: FnMut() + 'static>(&mut self, callback: F) {
+        self.on_complete = Some(Box::new(callback));
+    }
+
+    /// Starts playing `step`, replacing any animation already playing. Steps
+    /// that take no time, like callbacks, run immediately.
+    pub fn play(&mut self, step: Step<D>) {
+        let mut node = Node::new(step);
+        node.start(&mut self.springs, self.tolerance);
+        self.playing = Some(node);
+        self.finish_if_done();
+    }
+
+    /// Stops the playing animation without running the completion callback.
+    /// The springs keep moving towards their current targets.
+    pub fn stop(&mut self) {
+        self.playing = None;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing.is_some()
+    }
+
+    /// Returns `true` if nothing is playing and every spring has settled, so
+    /// the timeline no longer needs to be ticked.
+    pub fn is_at_rest(&self) -> bool {
+        !self.is_playing()
+            && self
+                .springs
+                .iter()
+                .all(|spring| spring.is_at_rest(self.tolerance))
+    }
+
+    /// Advances the springs and the playing animation by `delta_time`
+    /// seconds.
+    pub fn tick(&mut self, delta_time: Real) {
+        let mut remaining = delta_time;
+        while remaining > 0.0 {
+            // Stop at each event so targets change at the exact time
+            let step = match &self.playing {
+                Some(node) => node.time_left().min(remaining),
+                None => remaining,
+            };
+            for spring in self.springs.iter_mut() {
+                spring.step_by(step);
+            }
+            if let Some(node) = &mut self.playing {
+                node.advance(step, &mut self.springs, self.tolerance);
+            }
+            self.finish_if_done();
+            remaining -= step;
+        }
+    }
+
+    fn finish_if_done(&mut self) {
+        if self.playing.as_ref().is_some_and(|node| node.finished) {
+            self.playing = None;
+            if let Some(callback) = &mut self.on_complete {
+                callback();
+            }
+        }
+    }
+}
+
+/// A [`Step`] along with its progress.
+struct Node<const D: usize> {
+    kind: Kind<D>,
+    remaining: Real, // Time left for `To` and `Delay` (seconds)
+    finished: bool,
+}
+
+enum Kind<const D: usize> {
+    To {
+        spring: usize,
+        target: SVector<Real, D>,
+    },
+    Delay(Real),
+    Call(Box<dyn FnMut()>),
+    Sequence {
+        steps: Vec<Node<D>>,
+        current: usize,
+    },
+    Parallel(Vec<Node<D>>),
+}
+
+impl<const D: usize> Node<D> {
+    fn new(step: Step<D>) -> Self {
+        let kind = match step {
+            Step::To { spring, target } => Kind::To { spring, target },
+            Step::Delay(duration) => Kind::Delay(duration),
+            Step::Call(callback) => Kind::Call(callback),
+            Step::Sequence(steps) => Kind::Sequence {
+                steps: steps.into_iter().map(Node::new).collect(),
+                current: 0,
+            },
+            Step::Parallel(steps) => Kind::Parallel(steps.into_iter().map(Node::new).collect()),
+        };
+        Node {
+            kind,
+            remaining: 0.0,
+            finished: false,
+        }
+    }
+
+    fn start(&mut self, springs: &mut [VectorSpring<D>], tolerance: Real) {
+        match &mut self.kind {
+            Kind::To { spring, target } => {
+                springs[*spring].set_target(*target);
+                self.remaining = springs[*spring].settle_time(tolerance);
+                self.finished = self.remaining <= 0.0;
+            }
+            Kind::Delay(duration) => {
+                self.remaining = *duration;
+                self.finished = self.remaining <= 0.0;
+            }
+            Kind::Call(callback) => {
+                callback();
+                self.finished = true;
+            }
+            Kind::Sequence { steps, current } => {
+                *current = 0;
+                self.finished = start_next(steps, current, springs, tolerance);
+            }
+            Kind::Parallel(steps) => {
+                for step in steps.iter_mut() {
+                    step.start(springs, tolerance);
+                }
+                self.finished = steps.iter().all(|step| step.finished);
+            }
+        }
+    }
+
+    /// Returns the time until the next step starts or finishes.
+    fn time_left(&self) -> Real {
+        if self.finished {
+            return Real::INFINITY;
+        }
+        match &self.kind {
+            Kind::To { .. } | Kind::Delay(_) => self.remaining,
+            Kind::Call(_) => 0.0,
+            Kind::Sequence { steps, current } => steps[*current].time_left(),
+            Kind::Parallel(steps) => steps
+                .iter()
+                .map(|step| step.time_left())
+                .fold(Real::INFINITY, Real::min),
+        }
+    }
+
+    /// Moves time forward by at most [`Node::time_left`], starting any steps
+    /// that are due.
+    fn advance(&mut self, delta_time: Real, springs: &mut [VectorSpring<D>], tolerance: Real) {
+        if self.finished {
+            return;
+        }
+        match &mut self.kind {
+            Kind::To { .. } | Kind::Delay(_) => {
+                self.remaining -= delta_time;
+                self.finished = self.remaining <= 0.0;
+            }
+            Kind::Call(_) => {}
+            Kind::Sequence { steps, current } => {
+                steps[*current].advance(delta_time, springs, tolerance);
+                if steps[*current].finished {
+                    *current += 1;
+                    self.finished = start_next(steps, current, springs, tolerance);
+                }
+            }
+            Kind::Parallel(steps) => {
+                for step in steps.iter_mut() {
+                    step.advance(delta_time, springs, tolerance);
+                }
+                self.finished = steps.iter().all(|step| step.finished);
+            }
+        }
+    }
+}
+
+/// Starts steps of a sequence from `current`, skipping past any that finish
+/// instantly. Returns `true` if the whole sequence has finished.
+fn start_next<const D: usize>(
+    steps: &mut [Node<D>],
+    current: &mut usize,
+    springs: &mut [VectorSpring<D>],
+    tolerance: Real,
+) -> bool {
+    while *current < steps.len() {
+        steps[*current].start(springs, tolerance);
+        if !steps[*current].finished {
+            return false;
+        }
+        *current += 1;
+    }
+    true
+}
@@ -1,16 +1,18 @@
 use nalgebra::base::Vector2;
-use std::{collections::HashSet, time::Duration};
+use std::time::Duration;
+
+use crate::Real;
 
 pub struct Particle {
-    position: Vector2<f64>,
-    previous_position: Vector2<f64>,
-    acceleration: Vector2<f64>,
-    mass: f64,
+    position: Vector2<Real>,
+    previous_position: Vector2<Real>,
+    acceleration: Vector2<Real>,
+    mass: Real,
     pinned: bool,
 }
 
 impl Particle {
-    fn new(position: Vector2<f64>, pinned: bool) -> Self {
+    fn new(position: Vector2<Real>, pinned: bool) -> Self {
         Particle {
             position,
             previous_position: position,
@@ -20,12 +22,12 @@ impl Particle {
         }
     }
 
-    fn apply_force(&mut self, force: Vector2<f64>) {
+    fn apply_force(&mut self, force: Vector2<Real>) {
         self.acceleration += force / self.mass;
     }
 
     /// Update based on number of seconds since last update
-    fn update(&mut self, delta_time: f64) {
+    fn update(&mut self, delta_time: Real) {
         if self.pinned {
             return;
         }
@@ -39,10 +41,10 @@ impl Particle {
         self.position = new_pos;
     }
 
-    pub fn position(&self) -> Vector2<f64> {
+    pub fn position(&self) -> Vector2<Real> {
         self.position
     }
-    fn set_position(&mut self, position: Vector2<f64>) {
+    fn set_position(&mut self, position: Vector2<Real>) {
         self.position = position;
         self.acceleration = Vector2::zeros();
     }
@@ -54,11 +56,11 @@ impl Particle {
 pub struct Constraint {
     particle_a: usize,
     particle_b: usize,
-    rest_length: f64,
+    rest_length: Real,
 }
 
 impl Constraint {
-    pub fn new(particle_a: usize, particle_b: usize, rest_length: f64) -> Self {
+    pub fn new(particle_a: usize, particle_b: usize, rest_length: Real) -> Self {
         Constraint {
             particle_a,
             particle_b,
@@ -70,7 +72,7 @@ impl Constraint {
         (self.particle_a, self.particle_b)
     }
 
-    pub fn rest_length(&self) -> f64 {
+    pub fn rest_length(&self) -> Real {
         self.rest_length
     }
 }
@@ -81,18 +83,18 @@ pub struct Cloth {
     width: usize,
     height: usize,
     selected_particles: Vec<usize>,
-    selection_offsets: Vec<Vector2<f64>>, // Stores offsets from mouse position
+    selection_offsets: Vec<Vector2<Real>>, // Stores offsets from mouse position
 }
 
 impl Cloth {
-    pub fn new(width: usize, height: usize, spacing: f64) -> Self {
+    pub fn new(width: usize, height: usize, spacing: Real) -> Self {
         let mut particles = Vec::new();
         let mut constraints = Vec::new();
 
         // Create particles
         for y in 0..height {
             for x in 0..width {
-                let position = Vector2::new(x as f64 * spacing, y as f64 * spacing);
+                let position = Vector2::new(x as Real * spacing, y as Real * spacing);
                 // Pin the top row of particles to simulate hanging cloth
                 let pinned = y == 0 && x % 2 == 0;
                 particles.push(Particle::new(position, pinned));
@@ -127,7 +129,7 @@ impl Cloth {
     }
 
     pub fn simulate(&mut self, delta_time: Duration) {
-        let delta_time = delta_time.as_secs_f64();
+        let delta_time = delta_time.as_secs_f64() as Real;
         // update points
         for particle in self.particles.iter_mut() {
             particle.apply_force(gravity());
@@ -142,7 +144,7 @@ impl Cloth {
 
                 let diff = p0_pos - p1_pos;
                 let dist = diff.norm();
-                if diff.magnitude() < f64::EPSILON {
+                if diff.magnitude() < Real::EPSILON {
                     continue;
                 }
                 let diff_factor = (constraint.rest_length() - dist) / dist;
@@ -203,7 +205,7 @@ impl Cloth {
         });
     }
 
-    pub fn cut_at_mouse(&mut self, mouse_position: Vector2<f64>) {
+    pub fn cut_at_mouse(&mut self, mouse_position: Vector2<Real>) {
         // Find the nearest particle to the mouse position
         let (nearest_particle_index, distance) = self
             .particles()
@@ -220,7 +222,7 @@ impl Cloth {
         }
     }
 
-    pub fn select_particles(&mut self, mouse_pos: Vector2<f64>, radius: f64) {
+    pub fn select_particles(&mut self, mouse_pos: Vector2<Real>, radius: Real) {
         self.selected_particles.clear();
         self.selection_offsets.clear();
         for (i, particle) in self.particles.iter_mut().enumerate() {
@@ -233,7 +235,7 @@ impl Cloth {
             }
         }
     }
-    pub fn move_selected_particles(&mut self, mouse_pos: Vector2<f64>) {
+    pub fn move_selected_particles(&mut self, mouse_pos: Vector2<Real>) {
         for (idx, &particle_index) in self.selected_particles.iter().enumerate() {
             let offset = self.selection_offsets[idx];
             let new_position = mouse_pos + offset;
@@ -255,8 +257,8 @@ impl Cloth {
 }
 
 /// 987 pixels per second squared
-const GRAVITY: f64 = 987.;
+const GRAVITY: Real = 987.;
 //functions to return forces
-pub fn gravity() -> Vector2<f64> {
+pub fn gravity() -> Vector2<Real> {
     Vector2::new(0.0, GRAVITY)
 }
@@ -0,0 +1,309 @@
+use nalgebra::SVector;
+use std::time::Duration;
+
+use crate::projectile_motion::{self, Drag};
+use crate::Real;
+
+/// A single particle spawned by an [`Emitter`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle<const D: usize = 2> {
+    position: SVector<Real, D>,
+    velocity: SVector<Real, D>,
+    age: Real,      // Time since the particle was spawned (seconds)
+    lifetime: Real, // Age at which the particle dies (seconds)
+}
+
+impl<const D: usize> Particle<D> {
+    pub fn position(&self) -> SVector<Real, D> {
+        self.position
+    }
+
+    pub fn velocity(&self) -> SVector<Real, D> {
+        self.velocity
+    }
+
+    /// Returns the time since the particle was spawned, in seconds.
+    pub fn age(&self) -> Real {
+        self.age
+    }
+
+    pub fn lifetime(&self) -> Real {
+        self.lifetime
+    }
+
+    /// Returns how far through its life the particle is, from 0 to 1. Useful
+    /// for fading particles out.
+    pub fn life_fraction(&self) -> Real {
+        (self.age / self.lifetime).min(1.0)
+    }
+}
+
+/// Spawns and simulates large numbers of short-lived projectiles, for effects
+/// like sparks, fireworks and debris.
+///
+/// Particles are launched inside a cone around the emitter's direction, with
+/// randomized speed and lifetime. The random numbers come from a seeded
+/// generator, so the same seed always produces the same effect. Live particles
+/// are kept in one contiguous buffer, and the slots of dead particles are
+/// reused by new ones.
+///
+/// ```rust
+/// use miniphys::emitter::Emitter;
+/// use miniphys::Real;
+/// use nalgebra::Vector2;
+/// use std::time::Duration;
+///
+/// let sparks = || {
+///     let gravity = Vector2::new(0.0, -9.81);
+///     let mut sparks = Emitter::new(Vector2::zeros(), Vector2::new(0.0, 1.0), gravity, 42);
+///     sparks.set_rate(500.0);
+///     sparks.set_spread(0.3);
+///     sparks.burst(100);
+///     sparks
+/// };
+///
+/// // Every particle starts within the cone
+/// let mut first = sparks();
+/// for particle in first.particles() {
+///     let velocity = particle.velocity();
+///     assert!(velocity.y / velocity.norm() >= (0.3 as Real).cos() - 1e-6);
+/// }
+///
+/// // The same seed plays out exactly the same way
+/// let mut second = sparks();
+/// for _ in 0..60 {
+///     first.update(Duration::from_millis(16));
+///     second.update(Duration::from_millis(16));
+/// }
+/// assert!(!first.is_empty());
+/// assert_eq!(first.particles(), second.particles());
+/// for particle in first.particles() {
+///     let _ = (particle.position(), particle.life_fraction());
+/// }
+/// ```
+pub struct Emitter<const D: usize = 2> {
+    particles: Vec<Particle<D>>,
+    position: SVector<Real, D>,
+    direction: SVector<Real, D>, // Unit vector at the centre of the cone
+    spread: Real,                // Half-angle of the cone (radians)
+    speed: (Real, Real),         // Range of launch speeds
+    lifetime: (Real, Real),      // Range of lifetimes (seconds)
+    rate: Real,                  // Particles spawned per second
+    pending: Real,               // Fractional particles waiting to be spawned
+    max_particles: usize,
+    gravity: SVector<Real, D>,
+    drag: Drag,
+    mass: Real,
+    rng: Rng,
+}
+
+impl<const D: usize> Emitter<D> {
+    /// Creates an emitter at `position` firing along `direction`, seeded for
+    /// reproducible randomness. It starts with a rate of zero, so particles
+    /// only appear from [`Emitter::burst`] until a rate is set.
+    pub fn new(
+        position: SVector<Real, D>,
+        direction: SVector<Real, D>,
+        gravity: SVector<Real, D>,
+        seed: u64,
+    ) -> Self {
+        Emitter {
+            particles: Vec::new(),
+            position,
+            direction: direction.normalize(),
+            spread: 0.0,
+            speed: (1.0, 1.0),
+            lifetime: (1.0, 1.0),
+            rate: 0.0,
+            pending: 0.0,
+            max_particles: 10_000,
+            gravity,
+            drag: Drag::None,
+            mass: 1.0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn set_position(&mut self, position: SVector<Real, D>) {
+        self.position = position;
+    }
+
+    pub fn set_direction(&mut self, direction: SVector<Real, D>) {
+        self.direction = direction.normalize();
+    }
+
+    /// Sets the half-angle of the launch cone, in radians. Ignored in 1D.
+    pub fn set_spread(&mut self, spread: Real) {
+        self.spread = spread;
+    }
+
+    /// Sets the range launch speeds are drawn from.
+    pub fn set_speed(&mut self, min: Real, max: Real) {
+        self.speed = (min, max);
+    }
+
+    /// Sets the range lifetimes are drawn from, in seconds.
+    pub fn set_lifetime(&mut self, min: Real, max: Real) {
+        self.lifetime = (min, max);
+    }
+
+    /// Sets how many particles are spawned per second.
+    pub fn set_rate(&mut self, rate: Real) {
+        self.rate = rate;
+    }
+
+    /// Sets the most particles that can be alive at once. Spawns past the
+    /// limit are dropped.
+    pub fn set_max_particles(&mut self, max_particles: usize) {
+        self.max_particles = max_particles;
+    }
+
+    /// Sets the air resistance model applied to every particle.
+    pub fn set_drag(&mut self, drag: Drag) {
+        self.drag = drag;
+    }
+
+    /// Sets the mass of each particle (kg), which scales the effect of drag.
+    pub fn set_mass(&mut self, mass: Real) {
+        self.mass = mass;
+    }
+
+    /// Spawns `count` particles immediately.
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0..count {
+            self.spawn();
+        }
+    }
+
+    /// Ages and moves every particle, removes the ones that have died, then
+    /// spawns new particles at the emitter's rate.
+    pub fn update(&mut self, delta_time: Duration) {
+        let delta_time = delta_time.as_secs_f64() as Real;
+
+        let mut index = 0;
+        while index < self.particles.len() {
+            let particle = &mut self.particles[index];
+            particle.age += delta_time;
+            if particle.age >= particle.lifetime {
+                // Moves the last particle into this slot, keeping the buffer
+                // contiguous without shifting everything down
+                self.particles.swap_remove(index);
+                continue;
+            }
+            projectile_motion::step(
+                &mut particle.position,
+                &mut particle.velocity,
+                self.gravity,
+                &self.drag,
+                SVector::zeros(),
+                self.mass,
+                delta_time,
+            );
+            index += 1;
+        }
+
+        self.pending += self.rate * delta_time;
+        while self.pending >= 1.0 {
+            self.pending -= 1.0;
+            self.spawn();
+        }
+    }
+
+    fn spawn(&mut self) {
+        if self.particles.len() >= self.max_particles {
+            return;
+        }
+        let speed = self.rng.range(self.speed.0, self.speed.1);
+        let lifetime = self.rng.range(self.lifetime.0, self.lifetime.1);
+        let direction = self.cone_direction();
+        self.particles.push(Particle {
+            position: self.position,
+            velocity: direction * speed,
+            age: 0.0,
+            lifetime,
+        });
+    }
+
+    /// Picks a random direction within `spread` of the emitter's direction.
+    fn cone_direction(&mut self) -> SVector<Real, D> {
+        // There's no direction to spread into along a single axis
+        if self.spread <= 0.0 || D < 2 {
+            return self.direction;
+        }
+
+        // A random unit vector perpendicular to the cone's axis
+        let perpendicular = loop {
+            let sample = SVector::<Real, D>::from_fn(|_, _| self.rng.gaussian());
+            let sample = sample - self.direction * sample.dot(&self.direction);
+            if let Some(perpendicular) = sample.try_normalize(1e-9) {
+                break perpendicular;
+            }
+        };
+
+        // In 3D and up, a uniform cosine spreads particles evenly over the cap
+        let angle = if D <= 2 {
+            self.rng.range(0.0, self.spread)
+        } else {
+            self.rng.range(self.spread.cos(), 1.0).acos()
+        };
+        self.direction * angle.cos() + perpendicular * angle.sin()
+    }
+
+    /// Returns the live particles, for rendering.
+    pub fn particles(&self) -> &[Particle<D>] {
+        &self.particles
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Removes every particle.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.pending = 0.0;
+    }
+}
+
+/// A small SplitMix64 generator, so effects are reproducible from a seed
+/// without pulling in a dependency.
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniform value in `[0, 1)`.
+    fn next_float(&mut self) -> Real {
+        // Keep only as many bits as the mantissa holds, so the result is
+        // never rounded up to 1
+        let bits = Real::MANTISSA_DIGITS;
+        (self.next_u64() >> (64 - bits)) as Real / (1u64 << bits) as Real
+    }
+
+    fn range(&mut self, min: Real, max: Real) -> Real {
+        min + (max - min) * self.next_float()
+    }
+
+    /// Returns a standard normal value, using the Box-Muller transform.
+    fn gaussian(&mut self) -> Real {
+        let u1 = 1.0 - self.next_float();
+        let u2 = self.next_float();
+        (-2.0 * u1.ln()).sqrt() * (crate::consts::TAU * u2).cos()
+    }
+}
@@ -1,4 +1,33 @@
+#[cfg(feature = "f32")]
+use std::{f32 as float, primitive::f32 as Float};
+#[cfg(not(feature = "f32"))]
+use std::{f64 as float, primitive::f64 as Float};
+
+/// The floating point type used by every simulator: `f64`, or `f32` when the
+/// `f32` feature is enabled.
+///
+/// Cargo features are unified across a build, so if any crate in the
+/// dependency graph enables `f32`, every user of this crate gets `f32`.
+/// Applications should choose the precision; libraries built on this crate
+/// should leave the feature off and use `Real` rather than `f64` or `f32`.
+pub type Real = Float;
+
+/// Mathematical constants for [`Real`].
+pub use float::consts;
+
+/// Normalizes an angle in radians to (-π, π].
+pub(crate) fn wrap_angle(angle: Real) -> Real {
+    use consts::{PI, TAU};
+
+    angle - ((angle - PI) / TAU).ceil() * TAU
+}
+
+pub mod animation;
 pub mod cloth;
+pub mod emitter;
+pub mod mass_spring;
 pub mod pendulum;
+pub mod phase_space;
 pub mod projectile_motion;
+pub mod soft_body;
 pub mod spring;
@@ -0,0 +1,483 @@
+use nalgebra::{SMatrix, SVector};
+use std::time::Duration;
+
+use crate::Real;
+
+/// A point mass in a [`MassSpringSystem`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointMass<const D: usize = 2> {
+    position: SVector<Real, D>,
+    velocity: SVector<Real, D>,
+    force: SVector<Real, D>, // External force applied until the next update
+    mass: Real,              // (kg)
+    pinned: bool,
+}
+
+impl<const D: usize> PointMass<D> {
+    pub fn position(&self) -> SVector<Real, D> {
+        self.position
+    }
+
+    pub fn velocity(&self) -> SVector<Real, D> {
+        self.velocity
+    }
+
+    pub fn mass(&self) -> Real {
+        self.mass
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+}
+
+/// A damped Hooke's-law spring between two point masses.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpringLink {
+    mass_a: usize,
+    mass_b: usize,
+    stiffness: Real,   // (newtons per meter)
+    damping: Real,     // (newton seconds per meter) Along the spring only
+    rest_length: Real, // (meters)
+}
+
+impl SpringLink {
+    pub fn new(
+        mass_a: usize,
+        mass_b: usize,
+        stiffness: Real,
+        damping: Real,
+        rest_length: Real,
+    ) -> Self {
+        SpringLink {
+            mass_a,
+            mass_b,
+            stiffness,
+            damping,
+            rest_length,
+        }
+    }
+
+    pub fn masses(&self) -> (usize, usize) {
+        (self.mass_a, self.mass_b)
+    }
+
+    pub fn stiffness(&self) -> Real {
+        self.stiffness
+    }
+
+    pub fn damping(&self) -> Real {
+        self.damping
+    }
+
+    pub fn rest_length(&self) -> Real {
+        self.rest_length
+    }
+}
+
+/// How a [`MassSpringSystem`] advances its masses through time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Integrator {
+    /// Updates velocity, then position with the new velocity. Cheap and
+    /// stable for soft springs.
+    SemiImplicitEuler,
+    /// Velocity Verlet, which is second order and conserves energy well for
+    /// undamped springs.
+    Verlet,
+    /// Backward Euler, solving a linear system each step. Stays stable for
+    /// stiff springs and large time steps at the cost of some extra damping.
+    ImplicitEuler,
+}
+
+/// Point masses connected by damped springs, for jelly, soft bodies, ropes
+/// and bridges.
+///
+/// ```rust
+/// use miniphys::mass_spring::{Integrator, MassSpringSystem};
+/// use miniphys::Real;
+/// use nalgebra::Vector2;
+/// use std::time::Duration;
+///
+/// // A rope bridge pinned at both ends
+/// let mut bridge = MassSpringSystem::new(Vector2::new(0.0, -9.81), Integrator::ImplicitEuler);
+/// let planks: Vec<usize> = (0..=10)
+///     .map(|i| bridge.add_mass(Vector2::new(i as Real, 0.0), 1.0))
+///     .collect();
+/// bridge.set_pinned(planks[0], true);
+/// bridge.set_pinned(planks[10], true);
+/// for pair in planks.windows(2) {
+///     bridge.connect(pair[0], pair[1], 5000.0, 10.0);
+/// }
+///
+/// for _ in 0..100 {
+///     bridge.update(Duration::from_millis(16));
+/// }
+/// assert!(bridge.masses()[5].position().y < 0.0);
+/// ```
+pub struct MassSpringSystem<const D: usize = 2> {
+    masses: Vec<PointMass<D>>,
+    springs: Vec<SpringLink>,
+    gravity: SVector<Real, D>,
+    integrator: Integrator,
+}
+
+impl<const D: usize> MassSpringSystem<D> {
+    pub fn new(gravity: SVector<Real, D>, integrator: Integrator) -> Self {
+        MassSpringSystem {
+            masses: Vec::new(),
+            springs: Vec::new(),
+            gravity,
+            integrator,
+        }
+    }
+
+    /// Adds a mass at rest at `position` and returns its index.
+    pub fn add_mass(&mut self, position: SVector<Real, D>, mass: Real) -> usize {
+        self.masses.push(PointMass {
+            position,
+            velocity: SVector::zeros(),
+            force: SVector::zeros(),
+            mass,
+            pinned: false,
+        });
+        self.masses.len() - 1
+    }
+
+    /// Adds a spring and returns its index.
+    pub fn add_spring(&mut self, spring: SpringLink) -> usize {
+        self.springs.push(spring);
+        self.springs.len() - 1
+    }
+
+    /// Connects two masses with a spring whose rest length is their current
+    /// distance, and returns its index.
+    pub fn connect(
+        &mut self,
+        mass_a: usize,
+        mass_b: usize,
+        stiffness: Real,
+        damping: Real,
+    ) -> usize {
+        let rest_length = (self.masses[mass_b].position - self.masses[mass_a].position).norm();
+        self.add_spring(SpringLink::new(
+            mass_a,
+            mass_b,
+            stiffness,
+            damping,
+            rest_length,
+        ))
+    }
+
+    /// Pins a mass in place. Pinned masses ignore forces, but can still be
+    /// moved with [`MassSpringSystem::set_position`].
+    pub fn set_pinned(&mut self, index: usize, pinned: bool) {
+        self.masses[index].pinned = pinned;
+        self.masses[index].velocity = SVector::zeros();
+    }
+
+    pub fn set_position(&mut self, index: usize, position: SVector<Real, D>) {
+        self.masses[index].position = position;
+    }
+
+    pub fn set_velocity(&mut self, index: usize, velocity: SVector<Real, D>) {
+        self.masses[index].velocity = velocity;
+    }
+
+    /// Applies an external force (newtons) to a mass during the next update.
+    pub fn apply_force(&mut self, index: usize, force: SVector<Real, D>) {
+        self.masses[index].force += force;
+    }
+
+    pub fn set_gravity(&mut self, gravity: SVector<Real, D>) {
+        self.gravity = gravity;
+    }
+
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        self.integrator = integrator;
+    }
+
+    pub fn integrator(&self) -> Integrator {
+        self.integrator
+    }
+
+    pub fn masses(&self) -> &[PointMass<D>] {
+        &self.masses
+    }
+
+    pub fn springs(&self) -> &[SpringLink] {
+        &self.springs
+    }
+
+    /// Removes a spring, moving the last spring into its index.
+    pub fn remove_spring(&mut self, index: usize) {
+        if index < self.springs.len() {
+            self.springs.swap_remove(index);
+        }
+    }
+
+    /// Returns the total kinetic energy of the masses, in joules.
+    pub fn kinetic_energy(&self) -> Real {
+        self.masses
+            .iter()
+            .map(|mass| 0.5 * mass.mass * mass.velocity.norm_squared())
+            .sum()
+    }
+
+    /// Returns the energy stored in the springs plus the gravitational
+    /// potential energy of the masses, in joules.
+    pub fn potential_energy(&self) -> Real {
+        let elastic: Real = self
+            .springs
+            .iter()
+            .map(|spring| {
+                let length = (self.masses[spring.mass_b].position
+                    - self.masses[spring.mass_a].position)
+                    .norm();
+                0.5 * spring.stiffness * (length - spring.rest_length).powi(2)
+            })
+            .sum();
+        let gravitational: Real = self
+            .masses
+            .iter()
+            .map(|mass| -mass.mass * self.gravity.dot(&mass.position))
+            .sum();
+        elastic + gravitational
+    }
+
+    /// Advances every mass by `delta_time` with the selected integrator, then
+    /// clears the applied forces.
+    pub fn update(&mut self, delta_time: Duration) {
+        let delta_time = delta_time.as_secs_f64() as Real;
+        match self.integrator {
+            Integrator::SemiImplicitEuler => self.semi_implicit_euler(delta_time),
+            Integrator::Verlet => self.verlet(delta_time),
+            Integrator::ImplicitEuler => self.implicit_euler(delta_time),
+        }
+        for mass in self.masses.iter_mut() {
+            mass.force = SVector::zeros();
+        }
+    }
+
+    fn semi_implicit_euler(&mut self, delta_time: Real) {
+        let accelerations = self.accelerations();
+        for (mass, acceleration) in self.masses.iter_mut().zip(accelerations) {
+            if mass.pinned {
+                continue;
+            }
+            mass.velocity += acceleration * delta_time;
+            mass.position += mass.velocity * delta_time;
+        }
+    }
+
+    fn verlet(&mut self, delta_time: Real) {
+        let accelerations = self.accelerations();
+        let velocities: Vec<_> = self.masses.iter().map(|mass| mass.velocity).collect();
+        for (mass, acceleration) in self.masses.iter_mut().zip(&accelerations) {
+            if mass.pinned {
+                continue;
+            }
+            mass.position += mass.velocity * delta_time + acceleration * (0.5 * delta_time.powi(2));
+            // Estimate the end-of-step velocity so damping sees it
+            mass.velocity += acceleration * delta_time;
+        }
+
+        let new_accelerations = self.accelerations();
+        for (i, mass) in self.masses.iter_mut().enumerate() {
+            if mass.pinned {
+                continue;
+            }
+            mass.velocity =
+                velocities[i] + (accelerations[i] + new_accelerations[i]) * (0.5 * delta_time);
+        }
+    }
+
+    /// Linearized backward Euler: solves
+    /// `(M - dt ∂f/∂v - dt² ∂f/∂x) Δv = dt (f + dt ∂f/∂x v)` for the change in
+    /// velocity.
+    ///
+    /// The system is solved with preconditioned conjugate gradients, applying
+    /// the matrix one spring at a time (Baraff and Witkin, "Large Steps in
+    /// Cloth Simulation"), so a step costs time in proportion to the number of
+    /// springs instead of building and factoring a dense matrix.
+    fn implicit_euler(&mut self, delta_time: Real) {
+        let mut rhs: Vec<_> = self
+            .masses
+            .iter()
+            .map(|mass| (self.gravity * mass.mass + mass.force) * delta_time)
+            .collect();
+        let mut diagonal: Vec<_> = self
+            .masses
+            .iter()
+            .map(|mass| SVector::<Real, D>::repeat(mass.mass))
+            .collect();
+
+        // Each spring adds `block` to the diagonal blocks of both its masses
+        // and subtracts it from the two off-diagonal blocks
+        let mut blocks = Vec::with_capacity(self.springs.len());
+        for spring in self.springs.iter() {
+            let (a, b) = (spring.mass_a, spring.mass_b);
+            let Some((force, position_jacobian, velocity_jacobian)) = self.spring_terms(spring)
+            else {
+                continue;
+            };
+
+            // Force on `a`, plus the velocity-dependent part of the implicit
+            // step. `b` gets the opposite
+            let relative_velocity = self.masses[b].velocity - self.masses[a].velocity;
+            let impulse = (force + position_jacobian * relative_velocity * delta_time) * delta_time;
+            rhs[a] += impulse;
+            rhs[b] -= impulse;
+
+            let block = velocity_jacobian * delta_time + position_jacobian * delta_time.powi(2);
+            diagonal[a] += block.diagonal();
+            diagonal[b] += block.diagonal();
+            blocks.push((a, b, block));
+        }
+
+        // Pinned masses don't change velocity, so their rows are left out
+        let free: Vec<bool> = self.masses.iter().map(|mass| !mass.pinned).collect();
+        let multiply = |vector: &[SVector<Real, D>]| {
+            let mut product: Vec<_> = self
+                .masses
+                .iter()
+                .zip(vector)
+                .map(|(mass, value)| value * mass.mass)
+                .collect();
+            for (a, b, block) in blocks.iter() {
+                let change = block * (vector[*a] - vector[*b]);
+                product[*a] += change;
+                product[*b] -= change;
+            }
+            for (value, &free) in product.iter_mut().zip(&free) {
+                if !free {
+                    *value = SVector::zeros();
+                }
+            }
+            product
+        };
+        let precondition = |vector: &[SVector<Real, D>]| -> Vec<SVector<Real, D>> {
+            vector
+                .iter()
+                .zip(&diagonal)
+                .zip(&free)
+                .map(|((value, diagonal), &free)| {
+                    if free {
+                        value.zip_map(diagonal, |value, diagonal| {
+                            if diagonal > 0.0 {
+                                value / diagonal
+                            } else {
+                                value
+                            }
+                        })
+                    } else {
+                        SVector::zeros()
+                    }
+                })
+                .collect()
+        };
+        let dot = |a: &[SVector<Real, D>], b: &[SVector<Real, D>]| -> Real {
+            a.iter().zip(b).map(|(a, b)| a.dot(b)).sum()
+        };
+
+        for (value, &free) in rhs.iter_mut().zip(&free) {
+            if !free {
+                *value = SVector::zeros();
+            }
+        }
+        let mut delta_velocity = vec![SVector::<Real, D>::zeros(); self.masses.len()];
+        let mut residual = rhs;
+        let mut preconditioned = precondition(&residual);
+        let mut direction = preconditioned.clone();
+        let mut error = dot(&residual, &preconditioned);
+        let tolerance = error * Real::EPSILON;
+        for _ in 0..self.masses.len() * D {
+            if error <= tolerance {
+                break;
+            }
+            let product = multiply(&direction);
+            let curvature = dot(&direction, &product);
+            if curvature <= 0.0 {
+                // Only possible with a zero or negative mass
+                break;
+            }
+            let step = error / curvature;
+            for i in 0..delta_velocity.len() {
+                delta_velocity[i] += direction[i] * step;
+                residual[i] -= product[i] * step;
+            }
+            preconditioned = precondition(&residual);
+            let next_error = dot(&residual, &preconditioned);
+            let ratio = next_error / error;
+            for (direction, preconditioned) in direction.iter_mut().zip(&preconditioned) {
+                *direction = preconditioned + *direction * ratio;
+            }
+            error = next_error;
+        }
+
+        for (mass, delta_velocity) in self.masses.iter_mut().zip(delta_velocity) {
+            if mass.pinned {
+                continue;
+            }
+            mass.velocity += delta_velocity;
+            mass.position += mass.velocity * delta_time;
+        }
+    }
+
+    /// Returns the acceleration of every mass from gravity, applied forces
+    /// and springs.
+    fn accelerations(&self) -> Vec<SVector<Real, D>> {
+        let mut forces: Vec<_> = self
+            .masses
+            .iter()
+            .map(|mass| self.gravity * mass.mass + mass.force)
+            .collect();
+        for spring in self.springs.iter() {
+            if let Some((force, _, _)) = self.spring_terms(spring) {
+                forces[spring.mass_a] += force;
+                forces[spring.mass_b] -= force;
+            }
+        }
+        self.masses
+            .iter()
+            .zip(forces)
+            .map(|(mass, force)| {
+                if mass.pinned {
+                    SVector::zeros()
+                } else {
+                    force / mass.mass
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the force a spring puts on its first mass, along with the
+    /// derivatives of that force with respect to the second mass's position
+    /// and velocity. Returns `None` if the masses are on top of each other.
+    #[allow(clippy::type_complexity)]
+    fn spring_terms(
+        &self,
+        spring: &SpringLink,
+    ) -> Option<(SVector<Real, D>, SMatrix<Real, D, D>, SMatrix<Real, D, D>)> {
+        let a = &self.masses[spring.mass_a];
+        let b = &self.masses[spring.mass_b];
+        let offset = b.position - a.position;
+        let length = offset.norm();
+        if length < Real::EPSILON {
+            return None;
+        }
+        let direction = offset / length;
+
+        let stretch = length - spring.rest_length;
+        let closing_speed = (b.velocity - a.velocity).dot(&direction);
+        let force = direction * (spring.stiffness * stretch + spring.damping * closing_speed);
+
+        // The sideways term is dropped when compressed, which keeps the
+        // implicit system positive definite
+        let along = direction * direction.transpose();
+        let across = SMatrix::<Real, D, D>::identity() - along;
+        let position_jacobian =
+            (along + across * (1.0 - spring.rest_length / length).max(0.0)) * spring.stiffness;
+        let velocity_jacobian = along * spring.damping;
+        Some((force, position_jacobian, velocity_jacobian))
+    }
+}
@@ -1,43 +1,609 @@
+use nalgebra::base::Vector3;
 use std::time::Duration;
 
+use crate::Real;
+
+/// Angular velocity of the Earth's rotation (rad/s), relative to the stars.
+#[allow(clippy::excessive_precision)] // Rounded when `Real` is `f32`
+pub const EARTH_ANGULAR_VELOCITY: Real = 7.292_115_9e-5;
+
 pub struct Pendulum {
-    angle: f64, // Current angle from the vertical (radians)
-    angular_velocity: f64,
-    angular_acceleration: f64,
-    length: f64,  // Length of the pendulum (meters)
-    gravity: f64, // Acceleration due to gravity (m/s^2)
-    damping: f64, // Damping coefficient
+    angle: Real,      // Current angle from the vertical, in (-π, π] (radians)
+    revolutions: i64, // Signed number of times the pendulum has gone over the top
+    angular_velocity: Real,
+    angular_acceleration: Real,
+    length: Real,          // Length of the pendulum (meters)
+    gravity: Real,         // Acceleration due to gravity (m/s^2)
+    damping: Real,         // Damping coefficient
+    drive_amplitude: Real, // Amplitude of the driving acceleration (rad/s^2)
+    drive_frequency: Real, // Angular frequency of the drive (rad/s)
+    time: Real,            // Time elapsed since creation (seconds)
 }
 
 impl Pendulum {
-    pub fn new(length: f64, initial_angle_deg: f64, damping: f64) -> Self {
+    pub fn new(length: Real, initial_angle_deg: Real, damping: Real) -> Self {
+        let (angle, revolutions) = wrap_angle(initial_angle_deg.to_radians());
         Pendulum {
-            angle: initial_angle_deg.to_radians(),
+            angle,
+            revolutions,
             angular_velocity: 0.0,
             angular_acceleration: 0.0,
             length,
             gravity: 9.81,
             damping,
+            drive_amplitude: 0.0,
+            drive_frequency: 0.0,
+            time: 0.0,
         }
     }
 
+    /// Drives the pendulum with a periodic angular acceleration of
+    /// `amplitude * cos(angular_frequency * t)`.
+    pub fn set_drive(&mut self, amplitude: Real, angular_frequency: Real) {
+        self.drive_amplitude = amplitude;
+        self.drive_frequency = angular_frequency;
+    }
+
     pub fn update(&mut self, delta_time: Duration) {
-        let delta_time = delta_time.as_secs_f64();
+        self.step(delta_time.as_secs_f64() as Real, 0.0);
+    }
+
+    /// Advances the pendulum with an extra angular acceleration from forces
+    /// outside the pendulum itself, such as coupling springs.
+    fn step(&mut self, delta_time: Real, external_acceleration: Real) {
         // Equation of motion for a pendulum
         self.angular_acceleration = -self.gravity / self.length * self.angle.sin();
+        self.angular_acceleration += external_acceleration;
 
         // Apply damping
         self.angular_acceleration -= self.damping * self.angular_velocity;
 
+        // Apply the periodic drive
+        self.angular_acceleration +=
+            self.drive_amplitude * (self.drive_frequency * self.time).cos();
+
         // Update angular velocity and angle
         self.angular_velocity += self.angular_acceleration * delta_time;
         self.angle += self.angular_velocity * delta_time;
+        self.time += delta_time;
+
+        // Keep the angle in (-π, π] and count the revolutions instead
+        let (angle, revolutions) = wrap_angle(self.angle);
+        self.angle = angle;
+        self.revolutions += revolutions;
+    }
+
+    /// Returns the angle from the vertical in radians, normalized to (-π, π].
+    pub fn angle(&self) -> Real {
+        self.angle
+    }
+
+    /// Returns the total angle turned through, including full revolutions.
+    pub fn unwrapped_angle(&self) -> Real {
+        self.angle + self.revolutions as Real * crate::consts::TAU
+    }
+
+    /// Returns the signed number of full revolutions the pendulum has made
+    /// over the top. Counter-clockwise revolutions are positive.
+    ///
+    /// ```rust
+    /// use miniphys::consts::PI;
+    /// use miniphys::pendulum::Pendulum;
+    /// use std::time::Duration;
+    ///
+    /// // Driven hard enough to go over the top
+    /// let mut pendulum = Pendulum::new(9.81, 10.0, 0.5);
+    /// pendulum.set_drive(1.2, 2.0 / 3.0);
+    /// let mut previous = pendulum.unwrapped_angle();
+    /// let mut went_over = false;
+    /// for _ in 0..2000 {
+    ///     pendulum.update(Duration::from_millis(10));
+    ///     assert!(pendulum.angle() > -PI && pendulum.angle() <= PI);
+    ///     assert!((pendulum.unwrapped_angle() - previous).abs() < 0.1);
+    ///     previous = pendulum.unwrapped_angle();
+    ///     went_over |= pendulum.revolutions() != 0;
+    /// }
+    /// assert!(went_over);
+    /// ```
+    pub fn revolutions(&self) -> i64 {
+        self.revolutions
+    }
+
+    pub fn angular_velocity(&self) -> Real {
+        self.angular_velocity
     }
 
-    pub fn position(&self) -> (f64, f64) {
+    /// Returns the time simulated so far, in seconds.
+    pub fn time(&self) -> Real {
+        self.time
+    }
+
+    pub fn position(&self) -> (Real, Real) {
         // Calculate the x and y position based on the angle
         let x = self.length * self.angle.sin();
         let y = -self.length * self.angle.cos();
         (x, y)
     }
 }
+
+/// Normalizes an angle to (-π, π], returning the normalized angle and the
+/// number of whole turns that were removed.
+fn wrap_angle(angle: Real) -> (Real, i64) {
+    let wrapped = crate::wrap_angle(angle);
+    let revolutions = ((angle - wrapped) / crate::consts::TAU).round();
+    (wrapped, revolutions as i64)
+}
+
+/// A row of identical pendulums hanging side by side, with neighbouring bobs
+/// connected by Hookean springs.
+///
+/// Starting one pendulum swinging while the others hang still shows the energy
+/// slowly moving between them (beats). For small angles the motion is a sum
+/// of normal modes whose frequencies are given by
+/// [`CoupledPendulums::normal_mode_frequencies`].
+///
+/// ```rust
+/// use miniphys::consts::TAU;
+/// use miniphys::pendulum::CoupledPendulums;
+/// use miniphys::Real;
+/// use std::time::Duration;
+///
+/// // Swinging the two pendulums in opposite directions excites only the
+/// // antisymmetric mode
+/// let mut pendulums = CoupledPendulums::new(&[5.0, -5.0], 1.0, 1.0, 1.0, 0.5);
+///
+/// // Time the downward zero crossings of the first pendulum
+/// let mut crossings = Vec::new();
+/// let mut previous = pendulums.pendulums()[0].angle();
+/// for step in 1..=1000 {
+///     pendulums.update(Duration::from_millis(10));
+///     let angle = pendulums.pendulums()[0].angle();
+///     if previous > 0.0 && angle <= 0.0 {
+///         crossings.push((step as Real - angle / (angle - previous)) * 0.01);
+///     }
+///     previous = angle;
+/// }
+///
+/// let period = (crossings[crossings.len() - 1] - crossings[0]) / (crossings.len() - 1) as Real;
+/// let expected = pendulums.normal_mode_frequencies()[1];
+/// assert!((TAU / period - expected).abs() < 0.01 * expected);
+/// ```
+pub struct CoupledPendulums {
+    pendulums: Vec<Pendulum>,
+    mass: Real,      // Mass of each bob (kg)
+    stiffness: Real, // Spring constant of the coupling springs (N/m)
+    spacing: Real,   // Distance between pivots, and the rest length of the springs (meters)
+}
+
+impl CoupledPendulums {
+    /// Creates one pendulum per entry in `initial_angles_deg`, with pivots
+    /// `spacing` meters apart.
+    pub fn new(
+        initial_angles_deg: &[Real],
+        length: Real,
+        mass: Real,
+        stiffness: Real,
+        spacing: Real,
+    ) -> Self {
+        CoupledPendulums {
+            pendulums: initial_angles_deg
+                .iter()
+                .map(|&angle| Pendulum::new(length, angle, 0.0))
+                .collect(),
+            mass,
+            stiffness,
+            spacing,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: Duration) {
+        let delta_time = delta_time.as_secs_f64() as Real;
+        let bobs: Vec<(Real, Real)> = (0..self.pendulums.len())
+            .map(|i| self.bob_position(i))
+            .collect();
+
+        // Tangential acceleration on each bob from the springs on either side
+        let mut accelerations = vec![0.0; self.pendulums.len()];
+        for i in 0..bobs.len().saturating_sub(1) {
+            let (dx, dy) = (bobs[i + 1].0 - bobs[i].0, bobs[i + 1].1 - bobs[i].1);
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance < Real::EPSILON {
+                continue;
+            }
+            let tension = self.stiffness * (distance - self.spacing) / distance;
+            let force = (dx * tension, dy * tension);
+            accelerations[i] += self.tangential(i, force);
+            accelerations[i + 1] -= self.tangential(i + 1, force);
+        }
+
+        for (pendulum, acceleration) in self.pendulums.iter_mut().zip(accelerations) {
+            pendulum.step(delta_time, acceleration);
+        }
+    }
+
+    /// Converts a force on bob `index` into the angular acceleration it causes.
+    fn tangential(&self, index: usize, force: (Real, Real)) -> Real {
+        let pendulum = &self.pendulums[index];
+        let (sin, cos) = pendulum.angle.sin_cos();
+        (force.0 * cos + force.1 * sin) / (self.mass * pendulum.length)
+    }
+
+    /// Sets the angular damping coefficient of every pendulum.
+    pub fn set_damping(&mut self, damping: Real) {
+        for pendulum in self.pendulums.iter_mut() {
+            pendulum.damping = damping;
+        }
+    }
+
+    pub fn pendulums(&self) -> &Vec<Pendulum> {
+        &self.pendulums
+    }
+
+    /// Returns the position of a bob, with the first pivot at the origin.
+    pub fn bob_position(&self, index: usize) -> (Real, Real) {
+        let (x, y) = self.pendulums[index].position();
+        (x + index as Real * self.spacing, y)
+    }
+
+    /// Returns the kinetic plus gravitational energy of a single pendulum,
+    /// ignoring the energy stored in the springs.
+    pub fn pendulum_energy(&self, index: usize) -> Real {
+        let pendulum = &self.pendulums[index];
+        let kinetic = 0.5 * self.mass * (pendulum.length * pendulum.angular_velocity).powi(2);
+        let potential =
+            self.mass * pendulum.gravity * pendulum.length * (1.0 - pendulum.angle.cos());
+        kinetic + potential
+    }
+
+    /// Returns the small-angle normal-mode angular frequencies (rad/s), from
+    /// the in-phase mode upwards.
+    pub fn normal_mode_frequencies(&self) -> Vec<Real> {
+        let count = self.pendulums.len();
+        let Some(pendulum) = self.pendulums.first() else {
+            return Vec::new();
+        };
+        let gravity_term = pendulum.gravity / pendulum.length;
+        let coupling_term = self.stiffness / self.mass;
+        (0..count)
+            .map(|n| {
+                let s = (n as Real * crate::consts::PI / (2.0 * count as Real)).sin();
+                (gravity_term + 4.0 * coupling_term * s * s).sqrt()
+            })
+            .collect()
+    }
+}
+
+/// A pendulum whose rod is a Hookean spring, giving the bob both a radial and
+/// an angular degree of freedom.
+pub struct ElasticPendulum {
+    angle: Real, // Current angle from the vertical (radians)
+    angular_velocity: Real,
+    length: Real, // Current (stretched) length of the spring (meters)
+    radial_velocity: Real,
+    rest_length: Real, // Unstretched length of the spring (meters)
+    stiffness: Real,   // Spring constant (N/m)
+    mass: Real,        // Mass of the bob (kg)
+    gravity: Real,     // Acceleration due to gravity (m/s^2)
+    damping: Real,     // Damping coefficient
+}
+
+impl ElasticPendulum {
+    /// Creates an elastic pendulum hanging at its static equilibrium length,
+    /// displaced by `initial_angle_deg` from the vertical.
+    pub fn new(
+        rest_length: Real,
+        stiffness: Real,
+        mass: Real,
+        initial_angle_deg: Real,
+        damping: Real,
+    ) -> Self {
+        let gravity = 9.81;
+        ElasticPendulum {
+            angle: initial_angle_deg.to_radians(),
+            angular_velocity: 0.0,
+            length: rest_length + mass * gravity / stiffness,
+            radial_velocity: 0.0,
+            rest_length,
+            stiffness,
+            mass,
+            gravity,
+            damping,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: Duration) {
+        let delta_time = delta_time.as_secs_f64() as Real;
+        let extension = self.length - self.rest_length;
+
+        // Equations of motion in polar coordinates
+        let radial_acceleration = self.length * self.angular_velocity.powi(2)
+            + self.gravity * self.angle.cos()
+            - self.stiffness / self.mass * extension
+            - self.damping * self.radial_velocity;
+        let angular_acceleration = (-self.gravity * self.angle.sin()
+            - 2.0 * self.radial_velocity * self.angular_velocity)
+            / self.length
+            - self.damping * self.angular_velocity;
+
+        self.radial_velocity += radial_acceleration * delta_time;
+        self.angular_velocity += angular_acceleration * delta_time;
+        self.length += self.radial_velocity * delta_time;
+        self.angle += self.angular_velocity * delta_time;
+    }
+
+    /// Sets the current length of the spring, for example to start the
+    /// pendulum bouncing as well as swinging.
+    pub fn set_length(&mut self, length: Real) {
+        self.length = length;
+    }
+
+    pub fn angle(&self) -> Real {
+        self.angle
+    }
+
+    pub fn angular_velocity(&self) -> Real {
+        self.angular_velocity
+    }
+
+    pub fn length(&self) -> Real {
+        self.length
+    }
+
+    pub fn position(&self) -> (Real, Real) {
+        let x = self.length * self.angle.sin();
+        let y = -self.length * self.angle.cos();
+        (x, y)
+    }
+}
+
+/// An inverted pendulum balanced on a cart that moves along a horizontal
+/// track. The pole angle is measured from upright, so an angle of zero is the
+/// (unstable) balanced position.
+///
+/// A horizontal force can be applied to the cart each step, which makes this
+/// the classic cart-pole benchmark for testing controllers:
+///
+/// ```rust
+/// use miniphys::pendulum::CartPendulum;
+/// use std::time::Duration;
+///
+/// let mut cart = CartPendulum::new(1.0, 0.1, 0.5, 5.0);
+/// for _ in 0..1000 {
+///     let [x, velocity, angle, angular_velocity] = cart.state();
+///     // Linear state feedback, which also brings the cart back to the middle
+///     cart.set_force(40.0 * angle + 10.0 * angular_velocity + 2.0 * x + 4.0 * velocity);
+///     cart.update(Duration::from_millis(10));
+/// }
+/// assert!(cart.angle().abs() < 0.01);
+/// assert!(cart.cart_position().abs() < 0.01);
+/// ```
+pub struct CartPendulum {
+    position: Real, // Horizontal position of the cart (meters)
+    velocity: Real,
+    angle: Real, // Pole angle from upright (radians)
+    angular_velocity: Real,
+    cart_mass: Real,   // (kg)
+    pole_mass: Real,   // Mass at the end of the pole (kg)
+    pole_length: Real, // (meters)
+    gravity: Real,     // Acceleration due to gravity (m/s^2)
+    force: Real,       // Horizontal force applied to the cart (N)
+}
+
+impl CartPendulum {
+    pub fn new(
+        cart_mass: Real,
+        pole_mass: Real,
+        pole_length: Real,
+        initial_angle_deg: Real,
+    ) -> Self {
+        CartPendulum {
+            position: 0.0,
+            velocity: 0.0,
+            angle: initial_angle_deg.to_radians(),
+            angular_velocity: 0.0,
+            cart_mass,
+            pole_mass,
+            pole_length,
+            gravity: 9.81,
+            force: 0.0,
+        }
+    }
+
+    /// Sets the horizontal force applied to the cart. The force stays applied
+    /// until it is changed.
+    pub fn set_force(&mut self, force: Real) {
+        self.force = force;
+    }
+
+    pub fn update(&mut self, delta_time: Duration) {
+        let delta_time = delta_time.as_secs_f64() as Real;
+        let (sin, cos) = self.angle.sin_cos();
+        let m = self.pole_mass;
+        let l = self.pole_length;
+
+        // Equations of motion for a point mass on a massless pole
+        let acceleration = (self.force
+            + m * sin * (l * self.angular_velocity.powi(2) - self.gravity * cos))
+            / (self.cart_mass + m * sin * sin);
+        let angular_acceleration = (self.gravity * sin - acceleration * cos) / l;
+
+        self.velocity += acceleration * delta_time;
+        self.angular_velocity += angular_acceleration * delta_time;
+        self.position += self.velocity * delta_time;
+        self.angle += self.angular_velocity * delta_time;
+    }
+
+    /// Returns the state vector `[position, velocity, angle, angular_velocity]`.
+    pub fn state(&self) -> [Real; 4] {
+        [
+            self.position,
+            self.velocity,
+            self.angle,
+            self.angular_velocity,
+        ]
+    }
+
+    pub fn cart_position(&self) -> Real {
+        self.position
+    }
+
+    pub fn angle(&self) -> Real {
+        self.angle
+    }
+
+    pub fn angular_velocity(&self) -> Real {
+        self.angular_velocity
+    }
+
+    /// Returns the position of the end of the pole, with the cart's track at
+    /// `y = 0`.
+    pub fn pole_position(&self) -> (Real, Real) {
+        let x = self.position + self.pole_length * self.angle.sin();
+        let y = self.pole_length * self.angle.cos();
+        (x, y)
+    }
+}
+
+/// A pendulum free to swing in any direction, with the bob constrained to a
+/// sphere around the pivot.
+///
+/// Positions use a local frame with `x` pointing east, `y` north and `z` up,
+/// with the pivot at the origin. Enabling Earth's rotation adds the Coriolis
+/// force, which makes the swing plane precess like a Foucault pendulum:
+///
+/// ```rust
+/// use miniphys::consts::PI;
+/// use miniphys::pendulum::{SphericalPendulum, EARTH_ANGULAR_VELOCITY};
+/// use std::time::Duration;
+///
+/// // Spin the planet 100 times faster so the precession shows up quickly
+/// let mut pendulum = SphericalPendulum::new(67.0, 5.0, 0.0);
+/// pendulum.set_earth_rotation(48.85, EARTH_ANGULAR_VELOCITY * 100.0);
+///
+/// // Follow the swing plane for two whole swings, a little over 32 seconds
+/// let mut previous = pendulum.swing_plane_angle();
+/// let mut turned = 0.0;
+/// for _ in 0..3284 {
+///     pendulum.update(Duration::from_millis(10));
+///     let angle = pendulum.swing_plane_angle();
+///     let change = angle - previous;
+///     turned += change - (change / PI).round() * PI;
+///     previous = angle;
+/// }
+///
+/// // The plane turns clockwise, so the measured heading decreases
+/// let measured = -turned / 32.84;
+/// let expected = pendulum.expected_precession_rate();
+/// assert!((measured - expected).abs() < 0.01 * expected);
+/// ```
+pub struct SphericalPendulum {
+    position: Vector3<Real>, // Position of the bob relative to the pivot (meters)
+    velocity: Vector3<Real>,
+    length: Real,            // Length of the pendulum (meters)
+    gravity: Real,           // Acceleration due to gravity (m/s^2)
+    damping: Real,           // Damping coefficient
+    rotation: Vector3<Real>, // Angular velocity of the frame (rad/s)
+}
+
+impl SphericalPendulum {
+    /// Creates a pendulum at rest, displaced `initial_angle_deg` from the
+    /// vertical towards the compass heading `azimuth_deg` (0 is east, 90 is
+    /// north).
+    pub fn new(length: Real, initial_angle_deg: Real, azimuth_deg: Real) -> Self {
+        let (polar_sin, polar_cos) = initial_angle_deg.to_radians().sin_cos();
+        let (azimuth_sin, azimuth_cos) = azimuth_deg.to_radians().sin_cos();
+        SphericalPendulum {
+            position: Vector3::new(
+                length * polar_sin * azimuth_cos,
+                length * polar_sin * azimuth_sin,
+                -length * polar_cos,
+            ),
+            velocity: Vector3::zeros(),
+            length,
+            gravity: 9.81,
+            damping: 0.0,
+            rotation: Vector3::zeros(),
+        }
+    }
+
+    /// Puts the pendulum on a planet rotating at `angular_velocity` (rad/s),
+    /// at the given latitude. Use [`EARTH_ANGULAR_VELOCITY`] for the Earth.
+    pub fn set_earth_rotation(&mut self, latitude_deg: Real, angular_velocity: Real) {
+        let (sin, cos) = latitude_deg.to_radians().sin_cos();
+        self.rotation = Vector3::new(0.0, angular_velocity * cos, angular_velocity * sin);
+    }
+
+    pub fn set_damping(&mut self, damping: Real) {
+        self.damping = damping;
+    }
+
+    /// Sets the velocity of the bob. Any component along the rod is removed.
+    pub fn set_velocity(&mut self, velocity: Vector3<Real>) {
+        let radial = self.position / self.length;
+        self.velocity = velocity - radial * velocity.dot(&radial);
+    }
+
+    pub fn update(&mut self, delta_time: Duration) {
+        let delta_time = delta_time.as_secs_f64() as Real;
+
+        // Gravity, Coriolis and damping, then the rod tension needed to keep
+        // the bob on the sphere
+        let free = Vector3::new(0.0, 0.0, -self.gravity)
+            - 2.0 * self.rotation.cross(&self.velocity)
+            - self.damping * self.velocity;
+        let tension = -(self.position.dot(&free) + self.velocity.norm_squared())
+            / (self.length * self.length);
+        let acceleration = free + self.position * tension;
+
+        self.velocity += acceleration * delta_time;
+        self.position += self.velocity * delta_time;
+
+        // Project back onto the sphere to stop numerical drift
+        self.position = self.position.normalize() * self.length;
+        let radial = self.position / self.length;
+        self.velocity -= radial * self.velocity.dot(&radial);
+    }
+
+    pub fn position(&self) -> Vector3<Real> {
+        self.position
+    }
+
+    pub fn velocity(&self) -> Vector3<Real> {
+        self.velocity
+    }
+
+    /// Returns the angle from the downward vertical and the compass heading
+    /// of the bob, both in radians.
+    pub fn angles(&self) -> (Real, Real) {
+        let polar = (-self.position.z / self.length).clamp(-1.0, 1.0).acos();
+        let azimuth = self.position.y.atan2(self.position.x);
+        (polar, azimuth)
+    }
+
+    /// Returns the heading of the swing plane in radians, in `[-π/2, π/2)`.
+    ///
+    /// This is the major axis of the ellipse traced by the bob, estimated
+    /// from the current state using the small-angle approximation, so it
+    /// changes smoothly over a swing instead of following the bob.
+    pub fn swing_plane_angle(&self) -> Real {
+        let omega_squared = self.gravity / self.length;
+        let (x, y) = (self.position.x, self.position.y);
+        let (vx, vy) = (self.velocity.x, self.velocity.y);
+        let xx = x * x + vx * vx / omega_squared;
+        let yy = y * y + vy * vy / omega_squared;
+        let xy = x * y + vx * vy / omega_squared;
+        let angle = 0.5 * (2.0 * xy).atan2(xx - yy);
+        if angle >= crate::consts::FRAC_PI_2 {
+            angle - crate::consts::PI
+        } else {
+            angle
+        }
+    }
+
+    /// Returns the Foucault precession rate of the swing plane (rad/s),
+    /// `Ω sin(latitude)`. Positive values are clockwise seen from above, as in
+    /// the northern hemisphere.
+    pub fn expected_precession_rate(&self) -> Real {
+        self.rotation.z
+    }
+}
@@ -0,0 +1,205 @@
+use crate::consts::TAU;
+use std::io::{self, Write};
+
+use crate::pendulum::{CartPendulum, ElasticPendulum, Pendulum};
+use crate::Real;
+
+/// A simulator whose state can be plotted in phase space as a generalized
+/// position and its velocity.
+pub trait PhaseSpace {
+    /// Returns the current `(position, velocity)` pair.
+    fn phase_state(&self) -> (Real, Real);
+
+    /// Returns the period of the position if it wraps around, like an angle
+    /// kept within (-π, π]. Wrapped positions are centred on zero.
+    fn position_period(&self) -> Option<Real> {
+        None
+    }
+}
+
+impl PhaseSpace for Pendulum {
+    fn phase_state(&self) -> (Real, Real) {
+        (self.angle(), self.angular_velocity())
+    }
+
+    fn position_period(&self) -> Option<Real> {
+        Some(TAU)
+    }
+}
+
+impl PhaseSpace for ElasticPendulum {
+    fn phase_state(&self) -> (Real, Real) {
+        (self.angle(), self.angular_velocity())
+    }
+}
+
+impl PhaseSpace for CartPendulum {
+    fn phase_state(&self) -> (Real, Real) {
+        (self.angle(), self.angular_velocity())
+    }
+}
+
+/// A single sample of a phase-space trajectory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhasePoint {
+    pub time: Real,
+    pub position: Real,
+    pub velocity: Real,
+}
+
+/// Records the phase-space trajectory of a simulator, and optionally a
+/// Poincaré section sampled once per drive period at a fixed drive phase.
+///
+/// ```rust
+/// use miniphys::pendulum::Pendulum;
+/// use miniphys::phase_space::PhaseRecorder;
+/// use miniphys::consts::TAU;
+/// use std::time::Duration;
+///
+/// let drive_frequency = 2.0 / 3.0;
+/// let mut pendulum = Pendulum::new(9.81, 10.0, 0.5);
+/// pendulum.set_drive(1.2, drive_frequency);
+///
+/// // Sample a quarter of the way through each drive cycle
+/// let drive_period = TAU / drive_frequency;
+/// let mut recorder = PhaseRecorder::new();
+/// recorder.set_poincare_section(drive_period, TAU / 4.0);
+/// for _ in 0..10_000 {
+///     pendulum.update(Duration::from_millis(10));
+///     recorder.record(pendulum.time(), &pendulum);
+/// }
+///
+/// assert_eq!(recorder.poincare_section().len(), 11);
+/// for point in recorder.poincare_section() {
+///     let cycles = (point.time - drive_period / 4.0) / drive_period;
+///     assert!((cycles - cycles.round()).abs() < 1e-4);
+/// }
+///
+/// let mut csv = Vec::new();
+/// recorder.write_poincare_csv(&mut csv).unwrap();
+/// ```
+#[derive(Default)]
+pub struct PhaseRecorder {
+    trajectory: Vec<PhasePoint>,
+    section: Vec<PhasePoint>,
+    drive_period: Option<Real>,
+    drive_phase: Real,
+    position_period: Option<Real>,
+}
+
+impl PhaseRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables Poincaré section capture. A section point is recorded each time
+    /// the simulation passes `drive_phase` (radians) of a drive with the given
+    /// period (seconds), which must be positive and finite.
+    pub fn set_poincare_section(&mut self, drive_period: Real, drive_phase: Real) {
+        assert!(
+            drive_period > 0.0 && drive_period.is_finite(),
+            "the drive period must be positive and finite"
+        );
+        self.drive_period = Some(drive_period);
+        self.drive_phase = drive_phase.rem_euclid(TAU);
+    }
+
+    /// Sets the period of positions that wrap around, so Poincaré section
+    /// points between samples on either side of the seam are interpolated the
+    /// short way. [`PhaseRecorder::record`] sets this from the system.
+    pub fn set_position_period(&mut self, period: Option<Real>) {
+        self.position_period = period;
+    }
+
+    /// Samples the state of `system` at the given simulation time.
+    pub fn record<S: PhaseSpace>(&mut self, time: Real, system: &S) {
+        let (position, velocity) = system.phase_state();
+        self.position_period = system.position_period();
+        self.record_state(time, position, velocity);
+    }
+
+    /// Samples a raw `(position, velocity)` pair, for systems that don't
+    /// implement [`PhaseSpace`] such as a [`crate::spring::Spring`] driven by
+    /// hand.
+    pub fn record_state(&mut self, time: Real, position: Real, velocity: Real) {
+        let point = PhasePoint {
+            time,
+            position,
+            velocity,
+        };
+
+        if let (Some(period), Some(previous)) = (self.drive_period, self.trajectory.last()) {
+            // Find every section time that falls within the last step and
+            // interpolate the state at that time
+            let mut change = position - previous.position;
+            if let Some(position_period) = self.position_period {
+                change -= (change / position_period).round() * position_period;
+            }
+
+            let offset = self.drive_phase / TAU * period;
+            let mut cycle = ((previous.time - offset) / period).floor() + 1.0;
+            loop {
+                let section_time = offset + cycle * period;
+                if section_time > time || time <= previous.time {
+                    break;
+                }
+                let fraction = (section_time - previous.time) / (time - previous.time);
+                self.section.push(PhasePoint {
+                    time: section_time,
+                    position: self.wrap(previous.position + change * fraction),
+                    velocity: previous.velocity + (velocity - previous.velocity) * fraction,
+                });
+                cycle += 1.0;
+            }
+        }
+
+        self.trajectory.push(point);
+    }
+
+    /// Wraps a position to within half a period of zero, if it's periodic.
+    fn wrap(&self, position: Real) -> Real {
+        match self.position_period {
+            Some(period) => position - ((position - 0.5 * period) / period).ceil() * period,
+            None => position,
+        }
+    }
+
+    /// Returns every recorded sample in order.
+    pub fn trajectory(&self) -> &Vec<PhasePoint> {
+        &self.trajectory
+    }
+
+    /// Returns the recorded Poincaré section points in order.
+    pub fn poincare_section(&self) -> &Vec<PhasePoint> {
+        &self.section
+    }
+
+    /// Removes all recorded samples, keeping the section settings.
+    pub fn clear(&mut self) {
+        self.trajectory.clear();
+        self.section.clear();
+    }
+
+    /// Writes the trajectory as CSV with a `time,position,velocity` header.
+    pub fn write_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_points(writer, &self.trajectory)
+    }
+
+    /// Writes the Poincaré section as CSV with a `time,position,velocity`
+    /// header.
+    pub fn write_poincare_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_points(writer, &self.section)
+    }
+}
+
+fn write_points<W: Write>(mut writer: W, points: &[PhasePoint]) -> io::Result<()> {
+    writeln!(writer, "time,position,velocity")?;
+    for point in points {
+        writeln!(
+            writer,
+            "{},{},{}",
+            point.time, point.position, point.velocity
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use crate::consts::TAU;
+use nalgebra::{SMatrix, SVector, Vector2};
+use std::time::Duration;
+
+use crate::mass_spring::{Integrator, MassSpringSystem};
+use crate::Real;
+
+/// A closed mass-spring surface filled with gas, for balloons, blobs and
+/// other squishy objects.
+///
+/// The surface is a list of faces: edges (`[usize; 2]`) wound counter-clockwise
+/// in 2D, or triangles (`[usize; 3]`) wound counter-clockwise seen from
+/// outside in 3D. The gas follows Boyle's law, so the pressure pushing out on
+/// every face rises as the enclosed area or volume shrinks.
+///
+/// Shape matching can also be enabled, which pulls the masses back towards a
+/// rotated and translated copy of their rest shape so the body keeps its form.
+///
+/// ```rust
+/// use miniphys::soft_body::SoftBody;
+/// use nalgebra::Vector2;
+/// use std::time::Duration;
+///
+/// // A balloon hanging from its top mass. Without pressure it collapses
+/// let mut blob = SoftBody::circle(Vector2::new(0.0, 5.0), 1.0, 24, 1.0, 500.0, 1.0, Vector2::new(0.0, -9.81));
+/// let rest_area = blob.volume();
+/// blob.set_pressure(20.0);
+/// blob.system_mut().set_pinned(6, true);
+/// for _ in 0..60 {
+///     blob.update(Duration::from_millis(16));
+/// }
+/// assert!(blob.volume() > 0.9 * rest_area);
+/// ```
+pub struct SoftBody<const D: usize = 2> {
+    system: MassSpringSystem<D>,
+    faces: Vec<[usize; D]>,
+    gas: Real, // Pressure times volume, constant at a fixed temperature
+    shape_matching: Real,
+    rest_shape: Vec<SVector<Real, D>>, // Mass offsets from the rest centre of mass
+    rotation: SMatrix<Real, D, D>,     // Last rotation found by shape matching
+}
+
+impl<const D: usize> SoftBody<D> {
+    /// Creates a soft body from a mass-spring system and the faces of its
+    /// surface. The current positions of the masses are used as the rest
+    /// shape. Only 2D and 3D bodies are supported.
+    pub fn new(system: MassSpringSystem<D>, faces: Vec<[usize; D]>) -> Self {
+        assert!(D == 2 || D == 3, "soft bodies must be 2D or 3D");
+        let mut body = SoftBody {
+            system,
+            faces,
+            gas: 0.0,
+            shape_matching: 0.0,
+            rest_shape: Vec::new(),
+            rotation: SMatrix::identity(),
+        };
+        body.set_rest_shape();
+        body
+    }
+
+    /// Fills the body with gas at `pressure` (pascals) for its current
+    /// volume. Squashing the body raises the pressure in proportion.
+    pub fn set_pressure(&mut self, pressure: Real) {
+        self.gas = pressure * self.volume();
+    }
+
+    /// Returns the current gas pressure, in pascals.
+    pub fn pressure(&self) -> Real {
+        let volume = self.volume();
+        if volume <= Real::EPSILON {
+            0.0
+        } else {
+            self.gas / volume
+        }
+    }
+
+    /// Returns the enclosed area (2D) or volume (3D).
+    pub fn volume(&self) -> Real {
+        let masses = self.system.masses();
+        self.faces
+            .iter()
+            .map(|face| face_volume(face.map(|index| masses[index].position())))
+            .sum()
+    }
+
+    /// Enables shape matching with a stiffness from 0 (off) to 1 (rigid).
+    /// This is the fraction of the way each mass is pulled towards its place
+    /// in the rest shape every update.
+    pub fn set_shape_matching(&mut self, stiffness: Real) {
+        self.shape_matching = stiffness.clamp(0.0, 1.0);
+    }
+
+    /// Uses the current positions of the masses as the shape that shape
+    /// matching returns to.
+    pub fn set_rest_shape(&mut self) {
+        let centre = self.centre_of_mass();
+        self.rest_shape = self
+            .system
+            .masses()
+            .iter()
+            .map(|mass| mass.position() - centre)
+            .collect();
+        self.rotation = SMatrix::identity();
+    }
+
+    /// Returns the mass-weighted average position of the masses.
+    pub fn centre_of_mass(&self) -> SVector<Real, D> {
+        let masses = self.system.masses();
+        let total: Real = masses.iter().map(|mass| mass.mass()).sum();
+        masses
+            .iter()
+            .map(|mass| mass.position() * mass.mass())
+            .sum::<SVector<Real, D>>()
+            / total
+    }
+
+    pub fn faces(&self) -> &[[usize; D]] {
+        &self.faces
+    }
+
+    pub fn system(&self) -> &MassSpringSystem<D> {
+        &self.system
+    }
+
+    /// Returns the underlying mass-spring system, for pinning, dragging or
+    /// changing the integrator.
+    pub fn system_mut(&mut self) -> &mut MassSpringSystem<D> {
+        &mut self.system
+    }
+
+    /// Applies the gas pressure, advances the masses and springs, then pulls
+    /// the masses towards the rest shape if shape matching is enabled.
+    pub fn update(&mut self, delta_time: Duration) {
+        let pressure = self.pressure();
+        for face in self.faces.iter() {
+            let positions = face.map(|index| self.system.masses()[index].position());
+            // Spread the force on the face evenly over its corners
+            let force = face_normal(positions) * (pressure / D as Real);
+            for &index in face {
+                self.system.apply_force(index, force);
+            }
+        }
+
+        self.system.update(delta_time);
+
+        if self.shape_matching > 0.0 {
+            self.match_shape(delta_time.as_secs_f64() as Real);
+        }
+    }
+
+    /// Moves each mass part of the way to its goal position in the best-fit
+    /// rotation of the rest shape, adding the same change to its velocity.
+    fn match_shape(&mut self, delta_time: Real) {
+        let centre = self.centre_of_mass();
+        let covariance = self
+            .system
+            .masses()
+            .iter()
+            .zip(&self.rest_shape)
+            .map(|(mass, rest)| (mass.position() - centre) * rest.transpose() * mass.mass())
+            .sum::<SMatrix<Real, D, D>>();
+        if let Some(rotation) = polar_rotation(covariance) {
+            self.rotation = rotation;
+        }
+
+        for index in 0..self.rest_shape.len() {
+            let mass = self.system.masses()[index];
+            if mass.is_pinned() {
+                continue;
+            }
+            let goal = self.rotation * self.rest_shape[index] + centre;
+            let correction = (goal - mass.position()) * self.shape_matching;
+            self.system
+                .set_position(index, mass.position() + correction);
+            if delta_time > 0.0 {
+                self.system
+                    .set_velocity(index, mass.velocity() + correction / delta_time);
+            }
+        }
+    }
+}
+
+impl SoftBody<2> {
+    /// Creates a ring of `segments` masses with a total of `mass` kg, joined
+    /// by springs along the edge. Starts with no pressure, and uses
+    /// [`Integrator::ImplicitEuler`] so stiff edges stay stable.
+    pub fn circle(
+        centre: Vector2<Real>,
+        radius: Real,
+        segments: usize,
+        mass: Real,
+        stiffness: Real,
+        damping: Real,
+        gravity: Vector2<Real>,
+    ) -> Self {
+        let mut system = MassSpringSystem::new(gravity, Integrator::ImplicitEuler);
+        for i in 0..segments {
+            let angle = i as Real / segments as Real * TAU;
+            let offset = Vector2::new(angle.cos(), angle.sin()) * radius;
+            system.add_mass(centre + offset, mass / segments as Real);
+        }
+
+        let mut faces = Vec::with_capacity(segments);
+        for i in 0..segments {
+            let next = (i + 1) % segments;
+            system.connect(i, next, stiffness, damping);
+            faces.push([i, next]);
+        }
+        SoftBody::new(system, faces)
+    }
+}
+
+/// Returns the outward normal of a face, scaled by its length (2D) or area
+/// (3D).
+fn face_normal<const D: usize>(corners: [SVector<Real, D>; D]) -> SVector<Real, D> {
+    let mut normal = SVector::zeros();
+    if D == 2 {
+        let edge = corners[1] - corners[0];
+        normal[0] = edge[1];
+        normal[1] = -edge[0];
+    } else {
+        let u = corners[1] - corners[0];
+        let v = corners[2] - corners[0];
+        normal[0] = 0.5 * (u[1] * v[2] - u[2] * v[1]);
+        normal[1] = 0.5 * (u[2] * v[0] - u[0] * v[2]);
+        normal[2] = 0.5 * (u[0] * v[1] - u[1] * v[0]);
+    }
+    normal
+}
+
+/// Returns the signed area (2D) or volume (3D) between a face and the origin.
+/// Summed over a closed surface, this is the enclosed area or volume.
+fn face_volume<const D: usize>(corners: [SVector<Real, D>; D]) -> Real {
+    if D == 2 {
+        0.5 * (corners[0][0] * corners[1][1] - corners[1][0] * corners[0][1])
+    } else {
+        corners[0].dot(&face_normal(corners)) / 3.0
+    }
+}
+
+/// Finds the rotation part of a matrix by Higham's polar decomposition
+/// iteration. Returns `None` if the matrix is singular or includes a
+/// reflection, such as when a body has been crushed flat or turned inside out.
+fn polar_rotation<const D: usize>(matrix: SMatrix<Real, D, D>) -> Option<SMatrix<Real, D, D>> {
+    if determinant(&matrix) <= 0.0 {
+        return None;
+    }
+    let mut rotation = matrix;
+    for _ in 0..20 {
+        let next = (rotation + rotation.try_inverse()?.transpose()) * 0.5;
+        let change = (next - rotation).norm();
+        rotation = next;
+        if change < 1e-9 {
+            break;
+        }
+    }
+    Some(rotation)
+}
+
+/// Returns the determinant of a 2x2 or 3x3 matrix.
+fn determinant<const D: usize>(m: &SMatrix<Real, D, D>) -> Real {
+    if D == 2 {
+        m[(0, 0)] * m[(1, 1)] - m[(0, 1)] * m[(1, 0)]
+    } else {
+        m[(0, 0)] * (m[(1, 1)] * m[(2, 2)] - m[(1, 2)] * m[(2, 1)])
+            - m[(0, 1)] * (m[(1, 0)] * m[(2, 2)] - m[(1, 2)] * m[(2, 0)])
+            + m[(0, 2)] * (m[(1, 0)] * m[(2, 1)] - m[(1, 1)] * m[(2, 0)])
+    }
+}
@@ -27,7 +27,10 @@
   Ported to Rust by Ethan Wilkes in 2024.
 
 ******************************************************************************/
-use std::f64;
+use nalgebra::{SVector, UnitQuaternion, Vector3};
+
+use crate::consts;
+use crate::{wrap_angle, Real};
 
 /// An object representing a simplified damped harmonic oscillator, as written
 /// by [Ryan Juckett](http://www.ryanjuckett.com/). I have not tried to update
@@ -38,8 +41,9 @@ use std::f64;
 /// spring to a certain equillibrium point. This point can change (for example,
 /// if you wanted a circle to follow your mouse).
 ///
-/// The position and velocity values are one dimensional. If you would like to
-/// use this code to create 2D or 3D spring motion, try something like this:
+/// The position and velocity values are one dimensional. For 2D or 3D spring
+/// motion, [`VectorSpring`] keeps the state for every axis in one object. You
+/// can also update each axis yourself:
 ///
 /// ```rust
 /// use miniphys::spring::{fps, Spring};
@@ -54,11 +58,12 @@ use std::f64;
 /// (x_pos, x_vel) = spring_cache.update(x_pos, x_vel, 0.0);
 /// (y_pos, y_vel) = spring_cache.update(y_pos, y_vel, 0.0);
 /// ```
+#[derive(Clone, Copy, Debug)]
 pub struct Spring {
-    pos_pos_coef: f64,
-    pos_vel_coef: f64,
-    vel_pos_coef: f64,
-    vel_vel_coef: f64,
+    pos_pos_coef: Real,
+    pos_vel_coef: Real,
+    vel_pos_coef: Real,
+    vel_vel_coef: Real,
 }
 
 impl Spring {
@@ -73,7 +78,7 @@ impl Spring {
     /// - > 1: Over-damped (no oscillation, slower to equilibrium).
     /// - = 1: Critically damped (fastest to equilibrium without oscillation).
     /// - < 1: Under-damped (fastest to equilibrium with oscillation).
-    pub fn new(delta_time: f64, angular_frequency: f64, damping_ratio: f64) -> Self {
+    pub fn new(delta_time: Real, angular_frequency: Real, damping_ratio: Real) -> Self {
         // let epsilon = f64::EPSILON;
         let epsilon = 0.0001;
 
@@ -160,6 +165,15 @@ impl Spring {
         }
     }
 
+    /// Creates a new `Spring` instance from [`SpringParameters`].
+    pub fn from_parameters(delta_time: Real, parameters: SpringParameters) -> Self {
+        Spring::new(
+            delta_time,
+            parameters.angular_frequency,
+            parameters.damping_ratio,
+        )
+    }
+
     /// Updates the position and velocity values towards the equilibrium position.
     ///
     /// - `pos`: The current position.
@@ -167,7 +181,7 @@ impl Spring {
     /// - `equilibrium_pos`: The target equilibrium position.
     ///
     /// Returns the new position and velocity as a tuple.
-    pub fn update(&self, pos: f64, vel: f64, equilibrium_pos: f64) -> (f64, f64) {
+    pub fn update(&self, pos: Real, vel: Real, equilibrium_pos: Real) -> (Real, Real) {
         let old_pos = pos - equilibrium_pos;
         let old_vel = vel;
 
@@ -178,6 +192,491 @@ impl Spring {
     }
 }
 
+/// The angular frequency and damping ratio of a spring, with constructors
+/// for parameterizations that are easier to tune by hand.
+///
+/// ```rust
+/// use miniphys::spring::{fps, Spring, SpringParameters};
+///
+/// // Takes about half a second, with a little overshoot
+/// let parameters = SpringParameters::from_duration_and_bounce(0.5, 0.2);
+/// let spring = Spring::from_parameters(fps(60), parameters);
+/// assert!(parameters.damping_ratio() < 1.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpringParameters {
+    angular_frequency: Real,
+    damping_ratio: Real,
+}
+
+impl SpringParameters {
+    pub fn new(angular_frequency: Real, damping_ratio: Real) -> Self {
+        SpringParameters {
+            angular_frequency,
+            damping_ratio,
+        }
+    }
+
+    /// Creates parameters from a perceptual duration (seconds) and a bounce,
+    /// the way SwiftUI describes springs.
+    ///
+    /// The duration is the period of the undamped oscillation. A bounce of 0
+    /// is critically damped, positive values up to 1 overshoot more and more,
+    /// and negative values down to -1 are increasingly over-damped.
+    pub fn from_duration_and_bounce(duration: Real, bounce: Real) -> Self {
+        let damping_ratio = if bounce >= 0.0 {
+            1.0 - bounce
+        } else {
+            1.0 / (1.0 + bounce)
+        };
+        Self::new(consts::TAU / duration, damping_ratio)
+    }
+
+    /// Creates parameters that bring the spring within `epsilon` of its
+    /// target, as a fraction of the starting distance, after `settle_time`
+    /// seconds.
+    ///
+    /// The settle time is based on the exponential envelope of the motion,
+    /// so critically damped springs with a large initial velocity can take a
+    /// little longer.
+    pub fn from_settle_time(settle_time: Real, damping_ratio: Real, epsilon: Real) -> Self {
+        let decay = decay_factor(damping_ratio);
+        Self::new(-epsilon.ln() / (decay * settle_time), damping_ratio)
+    }
+
+    /// Creates critically damped parameters that close half the distance to
+    /// the target in `half_life` seconds, when released from rest.
+    ///
+    /// Later halvings take a little less time, since the spring is already
+    /// moving. For a critically damped spring the distance is
+    /// `(1 + ωt)e^(-ωt)` of the starting distance, which is ½ at
+    /// `ωt ≈ 1.678`.
+    pub fn from_half_life(half_life: Real) -> Self {
+        Self::new(HALF_LIFE_PHASE / half_life, 1.0)
+    }
+
+    /// Creates parameters from the physical constants of a mass on a spring:
+    /// `mass` (kg), `stiffness` (N/m) and `damping` (N·s/m).
+    pub fn from_physical(mass: Real, stiffness: Real, damping: Real) -> Self {
+        Self::new(
+            (stiffness / mass).sqrt(),
+            damping / (2.0 * (stiffness * mass).sqrt()),
+        )
+    }
+
+    pub fn angular_frequency(&self) -> Real {
+        self.angular_frequency
+    }
+
+    pub fn damping_ratio(&self) -> Real {
+        self.damping_ratio
+    }
+
+    /// Returns the perceptual duration, the inverse of
+    /// [`SpringParameters::from_duration_and_bounce`].
+    pub fn duration(&self) -> Real {
+        consts::TAU / self.angular_frequency
+    }
+
+    /// Returns the bounce, the inverse of
+    /// [`SpringParameters::from_duration_and_bounce`].
+    pub fn bounce(&self) -> Real {
+        if self.damping_ratio <= 1.0 {
+            1.0 - self.damping_ratio
+        } else {
+            1.0 / self.damping_ratio - 1.0
+        }
+    }
+
+    /// Returns the time to settle within `epsilon` of the target, the inverse
+    /// of [`SpringParameters::from_settle_time`].
+    pub fn settle_time(&self, epsilon: Real) -> Real {
+        -epsilon.ln() / (decay_factor(self.damping_ratio) * self.angular_frequency)
+    }
+
+    /// Returns the time for a spring released from rest to close half the
+    /// distance to its target, the inverse of
+    /// [`SpringParameters::from_half_life`].
+    pub fn half_life(&self) -> Real {
+        if self.damping_ratio == 1.0 {
+            return HALF_LIFE_PHASE / self.angular_frequency;
+        }
+
+        // Released from rest, the distance shrinks steadily until it first
+        // reaches the target, so bisect for the halfway point
+        let distance = |time: Real| self.evaluate(1.0, 0.0, 0.0, time).0;
+        let mut low = 0.0;
+        let mut high = 1.0 / self.angular_frequency;
+        for _ in 0..64 {
+            if distance(high) <= 0.5 {
+                break;
+            }
+            low = high;
+            high *= 2.0;
+        }
+        for _ in 0..64 {
+            let middle = 0.5 * (low + high);
+            if distance(middle) > 0.5 {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        high
+    }
+
+    /// Evaluates the spring's closed-form solution, returning the position and
+    /// velocity `time` seconds after starting from `pos` and `vel`.
+    pub fn evaluate(
+        &self,
+        pos: Real,
+        vel: Real,
+        equilibrium_pos: Real,
+        time: Real,
+    ) -> (Real, Real) {
+        Spring::from_parameters(time, *self).update(pos, vel, equilibrium_pos)
+    }
+
+    /// Returns how long a spring starting from `pos` and `vel` takes to come
+    /// to rest, after which both its distance from `equilibrium_pos` and its
+    /// speed stay within `tolerance`.
+    ///
+    /// This uses a bound on the motion rather than the motion itself, so it
+    /// never reports a spring as settled while an oscillation can still carry
+    /// it out of tolerance. Returns infinity for a spring with no stiffness or
+    /// no damping that isn't already at rest.
+    ///
+    /// ```rust
+    /// use miniphys::spring::SpringParameters;
+    ///
+    /// let parameters = SpringParameters::new(6.0, 0.5);
+    /// let settle_time = parameters.settle_time_from(0.0, 0.0, 100.0, 0.01);
+    /// let (pos, vel) = parameters.evaluate(0.0, 0.0, 100.0, settle_time);
+    /// assert!((pos - 100.0).abs() <= 0.01 && vel.abs() <= 0.01);
+    ///
+    /// // Starting inside the tolerance but moving outwards isn't settled yet
+    /// let parameters = SpringParameters::new(1.0, 1.0);
+    /// let settle_time = parameters.settle_time_from(1.0, 0.5, 0.0, 1.05);
+    /// assert!(parameters.evaluate(1.0, 0.5, 0.0, 0.33).0 > 1.05);
+    /// assert!(settle_time > 0.33);
+    /// ```
+    pub fn settle_time_from(
+        &self,
+        pos: Real,
+        vel: Real,
+        equilibrium_pos: Real,
+        tolerance: Real,
+    ) -> Real {
+        let x0 = pos - equilibrium_pos;
+        let v0 = vel;
+        let omega = self.angular_frequency.max(0.0);
+        let zeta = self.damping_ratio.max(0.0);
+        if omega < 0.0001 {
+            return if is_at_rest(pos, vel, equilibrium_pos, tolerance) {
+                0.0
+            } else {
+                Real::INFINITY
+            };
+        }
+
+        // Each case bounds |x(t)| and |v(t)| by an envelope that never
+        // increases, so it can be bisected
+        let envelope = if zeta < 1.0 {
+            let sigma = zeta * omega;
+            let alpha = omega * (1.0 - zeta * zeta).sqrt();
+            let b = (v0 + sigma * x0) / alpha;
+            let position = (x0 * x0 + b * b).sqrt();
+            let velocity = (v0 * v0 + (sigma * b + alpha * x0).powi(2)).sqrt();
+            let amplitude = position.max(velocity);
+            Envelope::Under { amplitude, sigma }
+        } else if zeta > 1.0 {
+            let root = omega * (zeta * zeta - 1.0).sqrt();
+            let (z1, z2) = (-omega * zeta - root, -omega * zeta + root);
+            let c2 = (v0 - z1 * x0) / (z2 - z1);
+            let c1 = x0 - c2;
+            Envelope::Over { z1, z2, c1, c2 }
+        } else {
+            // x(t) = (x0 + b t) e^(-ωt) and v(t) = (v0 - ω b t) e^(-ωt)
+            let a = x0.abs();
+            let b = (v0 + omega * x0).abs();
+            let c = v0.abs();
+            Envelope::Critical { a, b, c, omega }
+        };
+        let envelope = |time: Real| envelope.at(time);
+
+        if envelope(0.0) <= tolerance {
+            return 0.0;
+        }
+        // Find where the envelope drops below the tolerance
+        let mut low = 0.0;
+        let mut high = 1.0 / omega;
+        for _ in 0..64 {
+            if envelope(high) <= tolerance {
+                break;
+            }
+            low = high;
+            high *= 2.0;
+        }
+        if envelope(high) > tolerance {
+            // Undamped springs never settle
+            return Real::INFINITY;
+        }
+        for _ in 0..60 {
+            let middle = 0.5 * (low + high);
+            if envelope(middle) > tolerance {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        high
+    }
+
+    /// Returns the spring constant (N/m) for a given mass.
+    pub fn stiffness(&self, mass: Real) -> Real {
+        mass * self.angular_frequency * self.angular_frequency
+    }
+
+    /// Returns the damping coefficient (N·s/m) for a given mass.
+    pub fn damping(&self, mass: Real) -> Real {
+        2.0 * self.damping_ratio * mass * self.angular_frequency
+    }
+}
+
+/// A bound on the distance and speed of an under, over or critically damped
+/// spring from a given time onwards, used by
+/// [`SpringParameters::settle_time_from`].
+enum Envelope {
+    Under {
+        amplitude: Real,
+        sigma: Real, // Decay rate
+    },
+    Over {
+        z1: Real, // Decay rates of the two exponentials
+        z2: Real,
+        c1: Real,
+        c2: Real,
+    },
+    Critical {
+        a: Real,
+        b: Real,
+        c: Real,
+        omega: Real,
+    },
+}
+
+impl Envelope {
+    fn at(&self, time: Real) -> Real {
+        match *self {
+            Envelope::Under { amplitude, sigma } => amplitude * (-sigma * time).exp(),
+            Envelope::Over { z1, z2, c1, c2 } => {
+                let (e1, e2) = ((z1 * time).exp(), (z2 * time).exp());
+                let position = c1.abs() * e1 + c2.abs() * e2;
+                let velocity = (z1 * c1).abs() * e1 + (z2 * c2).abs() * e2;
+                position.max(velocity)
+            }
+            Envelope::Critical { a, b, c, omega } => {
+                // Each term rises to a single peak and then decays, so the
+                // largest value still to come is at the later of `time` and
+                // that peak
+                let term = |constant: Real, slope: Real| {
+                    let peak = if slope > 0.0 {
+                        (1.0 / omega - constant / slope).max(0.0)
+                    } else {
+                        0.0
+                    };
+                    let time = time.max(peak);
+                    (constant + slope * time) * (-omega * time).exp()
+                };
+                term(a, b).max(term(c, omega * b))
+            }
+        }
+    }
+}
+
+/// The value of `ωt` at which a critically damped spring released from rest
+/// has closed half the distance to its target, solving `(1 + ωt)e^(-ωt) = ½`.
+#[allow(clippy::excessive_precision)] // Rounded when `Real` is `f32`
+const HALF_LIFE_PHASE: Real = 1.678_346_990_016_660_3;
+
+/// Returns the decay rate of the slowest part of the motion, as a multiple of
+/// the angular frequency.
+fn decay_factor(damping_ratio: Real) -> Real {
+    if damping_ratio <= 1.0 {
+        damping_ratio
+    } else {
+        damping_ratio - (damping_ratio * damping_ratio - 1.0).sqrt()
+    }
+}
+
+/// Returns `true` if a spring is within `tolerance` of `equilibrium_pos` and
+/// moving slower than `tolerance`, so it can stop being updated.
+pub fn is_at_rest(pos: Real, vel: Real, equilibrium_pos: Real, tolerance: Real) -> bool {
+    (pos - equilibrium_pos).abs() <= tolerance && vel.abs() <= tolerance
+}
+
+/// Moves `current` towards `target` like Unity's `Mathf.SmoothDamp`, using a
+/// critically damped spring that takes roughly `smooth_time` seconds to
+/// arrive. `velocity` holds the spring's velocity between calls, and
+/// `max_speed` limits how fast it can move.
+///
+/// Returns the new value, which never overshoots the target.
+///
+/// ```rust
+/// use miniphys::spring::{fps, smooth_damp};
+///
+/// let (mut camera_x, mut velocity) = (0.0, 0.0);
+/// for _ in 0..60 {
+///     camera_x = smooth_damp(camera_x, 10.0, &mut velocity, 0.3, 100.0, fps(60));
+/// }
+/// assert!((camera_x - 10.0).abs() < 0.1);
+/// ```
+pub fn smooth_damp(
+    current: Real,
+    target: Real,
+    velocity: &mut Real,
+    smooth_time: Real,
+    max_speed: Real,
+    delta_time: Real,
+) -> Real {
+    smooth_damp_moving(
+        current,
+        target,
+        0.0,
+        velocity,
+        smooth_time,
+        max_speed,
+        delta_time,
+    )
+}
+
+/// Like [`smooth_damp`], for a target that moves at `target_velocity`.
+/// `target` is the target's position at the start of the step.
+///
+/// The spring works in a frame moving with the target, so following a target
+/// at a steady velocity doesn't leave `current` lagging behind it. `max_speed`
+/// limits the speed relative to the target.
+pub fn smooth_damp_moving(
+    current: Real,
+    target: Real,
+    target_velocity: Real,
+    velocity: &mut Real,
+    smooth_time: Real,
+    max_speed: Real,
+    delta_time: Real,
+) -> Real {
+    // Same relationship between smooth time and angular frequency as Unity
+    let smooth_time = smooth_time.max(0.0001);
+    let spring = Spring::new(delta_time, 2.0 / smooth_time, 1.0);
+
+    // Limiting the distance the spring starts from limits its top speed
+    let max_offset = max_speed * smooth_time;
+    let offset = (current - target).clamp(-max_offset, max_offset);
+    let (mut offset, mut relative_velocity) =
+        spring.update(offset, *velocity - target_velocity, 0.0);
+
+    // Stop at the target rather than passing it
+    if (current - target) * offset < 0.0 {
+        offset = 0.0;
+        relative_velocity = 0.0;
+    }
+
+    *velocity = relative_velocity + target_velocity;
+    target + target_velocity * delta_time + offset
+}
+
+/// Number of time steps whose coefficients a [`VariableSpring`] remembers.
+const CACHED_STEPS: usize = 4;
+
+/// A damped spring that can be advanced by a different time step on every
+/// update, for frame rates that aren't fixed.
+///
+/// Each update uses the exact closed-form solution for its time step, so the
+/// motion is the same no matter how time is split up. The coefficients for the
+/// most recently used time steps are cached, so a steady frame rate costs no
+/// more than a [`Spring`].
+///
+/// ```rust
+/// use miniphys::spring::VariableSpring;
+/// use miniphys::Real;
+///
+/// let mut spring = VariableSpring::new(6.0, 0.5);
+/// let (mut pos, mut vel) = (0.0, 0.0);
+/// for delta_time in [0.016, 0.033, 0.008] {
+///     (pos, vel) = spring.update(pos, vel, 10.0, delta_time);
+/// }
+///
+/// // Ten small steps land in the same place as one large one
+/// let (mut pos, mut vel) = (0.0, 0.0);
+/// for _ in 0..10 {
+///     (pos, vel) = spring.update(pos, vel, 10.0, 0.01);
+/// }
+/// let (large_pos, large_vel) = spring.update(0.0, 0.0, 10.0, 0.1);
+/// let tolerance = 4096.0 * Real::EPSILON; // Under 1e-12 in double precision
+/// assert!((pos - large_pos).abs() < tolerance && (vel - large_vel).abs() < tolerance);
+/// ```
+#[derive(Clone, Debug)]
+pub struct VariableSpring {
+    angular_frequency: Real,
+    damping_ratio: Real,
+    cache: [Option<(Real, Spring)>; CACHED_STEPS],
+    next_slot: usize, // Cache slot to overwrite next
+}
+
+impl VariableSpring {
+    /// Creates a spring with the same parameters as [`Spring::new`], minus
+    /// the time step.
+    pub fn new(angular_frequency: Real, damping_ratio: Real) -> Self {
+        VariableSpring {
+            angular_frequency,
+            damping_ratio,
+            cache: [None; CACHED_STEPS],
+            next_slot: 0,
+        }
+    }
+
+    pub fn from_parameters(parameters: SpringParameters) -> Self {
+        VariableSpring::new(parameters.angular_frequency, parameters.damping_ratio)
+    }
+
+    /// Updates the position and velocity values towards the equilibrium
+    /// position over `delta_time` seconds.
+    ///
+    /// Returns the new position and velocity as a tuple.
+    pub fn update(
+        &mut self,
+        pos: Real,
+        vel: Real,
+        equilibrium_pos: Real,
+        delta_time: Real,
+    ) -> (Real, Real) {
+        self.coefficients(delta_time)
+            .update(pos, vel, equilibrium_pos)
+    }
+
+    /// Returns the coefficients for a time step, computing and caching them
+    /// if they aren't cached already.
+    fn coefficients(&mut self, delta_time: Real) -> Spring {
+        if let Some((_, spring)) = self
+            .cache
+            .iter()
+            .flatten()
+            .find(|(cached_time, _)| *cached_time == delta_time)
+        {
+            return *spring;
+        }
+
+        let spring = Spring::new(delta_time, self.angular_frequency, self.damping_ratio);
+        self.cache[self.next_slot] = Some((delta_time, spring));
+        self.next_slot = (self.next_slot + 1) % CACHED_STEPS;
+        spring
+    }
+
+    pub fn parameters(&self) -> SpringParameters {
+        SpringParameters::new(self.angular_frequency, self.damping_ratio)
+    }
+}
+
 /// Calculates the time delta for a given number of frames per second.
 /// This value can be used as the time delta when initializing a `Spring`.
 ///
@@ -188,6 +687,366 @@ impl Spring {
 ///
 /// let delta_time = fps(60);
 /// ```
-pub fn fps(n: u32) -> f64 {
-    1.0 / n as f64
+pub fn fps(n: u32) -> Real {
+    1.0 / n as Real
+}
+
+/// A spring that owns its position, velocity and target, for values with up
+/// to four dimensions such as a point, a colour or a size.
+///
+/// ```rust
+/// use miniphys::spring::{fps, Spring2};
+/// use nalgebra::Vector2;
+///
+/// let mut follower = Spring2::new(fps(60), 6.0, 0.5, Vector2::zeros());
+/// follower.set_target(Vector2::new(100.0, 50.0));
+/// for _ in 0..60 {
+///     follower.step();
+/// }
+/// let position = follower.position();
+/// ```
+pub struct VectorSpring<const D: usize> {
+    spring: VariableSpring,
+    delta_time: Real, // Time step used by `step`
+    position: SVector<Real, D>,
+    velocity: SVector<Real, D>,
+    target: SVector<Real, D>,
+    target_velocity: SVector<Real, D>,
+}
+
+pub type Spring1 = VectorSpring<1>;
+pub type Spring2 = VectorSpring<2>;
+pub type Spring3 = VectorSpring<3>;
+pub type Spring4 = VectorSpring<4>;
+
+impl<const D: usize> VectorSpring<D> {
+    /// Creates a spring at rest at `position`, with the same parameters as
+    /// [`Spring::new`]. `delta_time` is the time step used by
+    /// [`VectorSpring::step`].
+    pub fn new(
+        delta_time: Real,
+        angular_frequency: Real,
+        damping_ratio: Real,
+        position: SVector<Real, D>,
+    ) -> Self {
+        VectorSpring {
+            spring: VariableSpring::new(angular_frequency, damping_ratio),
+            delta_time,
+            position,
+            velocity: SVector::zeros(),
+            target: position,
+            target_velocity: SVector::zeros(),
+        }
+    }
+
+    /// Creates a spring at rest at `position` from [`SpringParameters`].
+    pub fn from_parameters(
+        delta_time: Real,
+        parameters: SpringParameters,
+        position: SVector<Real, D>,
+    ) -> Self {
+        Self::new(
+            delta_time,
+            parameters.angular_frequency,
+            parameters.damping_ratio,
+            position,
+        )
+    }
+
+    /// Advances the spring by the time step it was created with.
+    pub fn step(&mut self) {
+        self.step_by(self.delta_time);
+    }
+
+    /// Advances the spring by `delta_time` seconds, for variable frame rates.
+    pub fn step_by(&mut self, delta_time: Real) {
+        // Spring towards the target in a frame moving along with it
+        let mut offset = self.position - self.target;
+        let mut relative_velocity = self.velocity - self.target_velocity;
+        let spring = self.spring.coefficients(delta_time);
+        for i in 0..D {
+            (offset[i], relative_velocity[i]) = spring.update(offset[i], relative_velocity[i], 0.0);
+        }
+        self.target += self.target_velocity * delta_time;
+        self.position = self.target + offset;
+        self.velocity = relative_velocity + self.target_velocity;
+    }
+
+    /// Sets the equilibrium point the spring moves towards.
+    pub fn set_target(&mut self, target: SVector<Real, D>) {
+        self.target = target;
+    }
+
+    /// Moves the target at a constant velocity on every step, so the spring
+    /// follows a moving object without lagging behind it.
+    pub fn set_target_velocity(&mut self, target_velocity: SVector<Real, D>) {
+        self.target_velocity = target_velocity;
+    }
+
+    /// Moves the spring immediately, keeping its velocity.
+    pub fn set_position(&mut self, position: SVector<Real, D>) {
+        self.position = position;
+    }
+
+    pub fn set_velocity(&mut self, velocity: SVector<Real, D>) {
+        self.velocity = velocity;
+    }
+
+    pub fn position(&self) -> SVector<Real, D> {
+        self.position
+    }
+
+    pub fn velocity(&self) -> SVector<Real, D> {
+        self.velocity
+    }
+
+    pub fn target(&self) -> SVector<Real, D> {
+        self.target
+    }
+
+    pub fn target_velocity(&self) -> SVector<Real, D> {
+        self.target_velocity
+    }
+
+    /// Returns `true` if the spring is within `tolerance` of its target and
+    /// moving slower than `tolerance` relative to it.
+    pub fn is_at_rest(&self, tolerance: Real) -> bool {
+        (self.position - self.target).norm() <= tolerance
+            && (self.velocity - self.target_velocity).norm() <= tolerance
+    }
+
+    /// Returns how long until the spring comes to rest within `tolerance`
+    /// relative to its target, if the target keeps its velocity. See
+    /// [`SpringParameters::settle_time_from`].
+    pub fn settle_time(&self, tolerance: Real) -> Real {
+        // Keeping every axis within tolerance / sqrt(D) keeps the length within
+        // tolerance
+        let axis_tolerance = tolerance / (D as Real).sqrt();
+        let parameters = self.spring.parameters();
+        (0..D)
+            .map(|i| {
+                parameters.settle_time_from(
+                    self.position[i],
+                    self.velocity[i] - self.target_velocity[i],
+                    self.target[i],
+                    axis_tolerance,
+                )
+            })
+            .fold(0.0, Real::max)
+    }
+}
+
+/// A spring for an angle in radians that always turns the shortest way
+/// around towards its target, so going from 10° to 350° passes through 0°.
+///
+/// ```rust
+/// use miniphys::spring::{fps, AngleSpring};
+/// use miniphys::Real;
+///
+/// let mut heading = AngleSpring::new(fps(60), 8.0, 1.0, Real::to_radians(10.0));
+/// heading.set_target(Real::to_radians(350.0));
+/// heading.step();
+/// assert!(heading.angular_velocity() < 0.0);
+/// ```
+pub struct AngleSpring {
+    spring: VariableSpring,
+    delta_time: Real,       // Time step used by `step`
+    angle: Real,            // (radians) Wrapped to (-π, π]
+    angular_velocity: Real, // (radians per second)
+    target: Real,           // (radians)
+}
+
+impl AngleSpring {
+    /// Creates a spring at rest at `angle`, with the same parameters as
+    /// [`Spring::new`]. `delta_time` is the time step used by
+    /// [`AngleSpring::step`].
+    pub fn new(
+        delta_time: Real,
+        angular_frequency: Real,
+        damping_ratio: Real,
+        angle: Real,
+    ) -> Self {
+        let angle = wrap_angle(angle);
+        AngleSpring {
+            spring: VariableSpring::new(angular_frequency, damping_ratio),
+            delta_time,
+            angle,
+            angular_velocity: 0.0,
+            target: angle,
+        }
+    }
+
+    /// Creates a spring at rest at `angle` from [`SpringParameters`].
+    pub fn from_parameters(delta_time: Real, parameters: SpringParameters, angle: Real) -> Self {
+        Self::new(
+            delta_time,
+            parameters.angular_frequency,
+            parameters.damping_ratio,
+            angle,
+        )
+    }
+
+    /// Advances the spring by the time step it was created with.
+    pub fn step(&mut self) {
+        self.step_by(self.delta_time);
+    }
+
+    /// Advances the spring by `delta_time` seconds, for variable frame rates.
+    pub fn step_by(&mut self, delta_time: Real) {
+        // Spring the shortest offset from the target towards zero
+        let offset = wrap_angle(self.angle - self.target);
+        let (offset, angular_velocity) =
+            self.spring
+                .update(offset, self.angular_velocity, 0.0, delta_time);
+        self.angle = wrap_angle(self.target + offset);
+        self.angular_velocity = angular_velocity;
+    }
+
+    /// Sets the angle the spring turns towards, in radians.
+    pub fn set_target(&mut self, target: Real) {
+        self.target = wrap_angle(target);
+    }
+
+    /// Moves the spring immediately, keeping its angular velocity.
+    pub fn set_angle(&mut self, angle: Real) {
+        self.angle = wrap_angle(angle);
+    }
+
+    pub fn set_angular_velocity(&mut self, angular_velocity: Real) {
+        self.angular_velocity = angular_velocity;
+    }
+
+    /// Returns the current angle in radians, wrapped to (-π, π].
+    pub fn angle(&self) -> Real {
+        self.angle
+    }
+
+    pub fn angular_velocity(&self) -> Real {
+        self.angular_velocity
+    }
+
+    /// Returns the target angle in radians, wrapped to (-π, π].
+    pub fn target(&self) -> Real {
+        self.target
+    }
+
+    /// Returns `true` if the spring is within `tolerance` radians of its
+    /// target the short way around, and turning slower than `tolerance`.
+    pub fn is_at_rest(&self, tolerance: Real) -> bool {
+        is_at_rest(
+            wrap_angle(self.angle - self.target),
+            self.angular_velocity,
+            0.0,
+            tolerance,
+        )
+    }
+}
+
+/// A spring for a 3D orientation that turns the shortest way towards its
+/// target rotation, for smoothing a camera or an object's facing.
+///
+/// The offset from the target is sprung as a rotation vector, so the motion
+/// stays on the shortest arc and is exact for rotations about a fixed axis.
+///
+/// ```rust
+/// use miniphys::spring::{fps, RotationSpring};
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let mut camera = RotationSpring::new(fps(60), 10.0, 1.0, UnitQuaternion::identity());
+/// camera.set_target(UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 1.0));
+/// for _ in 0..120 {
+///     camera.step();
+/// }
+/// assert!(camera.is_at_rest(0.001));
+/// ```
+pub struct RotationSpring {
+    spring: VariableSpring,
+    delta_time: Real, // Time step used by `step`
+    orientation: UnitQuaternion<Real>,
+    angular_velocity: Vector3<Real>, // (radians per second) About world axes
+    target: UnitQuaternion<Real>,
+}
+
+impl RotationSpring {
+    /// Creates a spring at rest at `orientation`, with the same parameters as
+    /// [`Spring::new`]. `delta_time` is the time step used by
+    /// [`RotationSpring::step`].
+    pub fn new(
+        delta_time: Real,
+        angular_frequency: Real,
+        damping_ratio: Real,
+        orientation: UnitQuaternion<Real>,
+    ) -> Self {
+        RotationSpring {
+            spring: VariableSpring::new(angular_frequency, damping_ratio),
+            delta_time,
+            orientation,
+            angular_velocity: Vector3::zeros(),
+            target: orientation,
+        }
+    }
+
+    /// Creates a spring at rest at `orientation` from [`SpringParameters`].
+    pub fn from_parameters(
+        delta_time: Real,
+        parameters: SpringParameters,
+        orientation: UnitQuaternion<Real>,
+    ) -> Self {
+        Self::new(
+            delta_time,
+            parameters.angular_frequency,
+            parameters.damping_ratio,
+            orientation,
+        )
+    }
+
+    /// Advances the spring by the time step it was created with.
+    pub fn step(&mut self) {
+        self.step_by(self.delta_time);
+    }
+
+    /// Advances the spring by `delta_time` seconds, for variable frame rates.
+    pub fn step_by(&mut self, delta_time: Real) {
+        // `scaled_axis` picks the shorter of the two arcs to the target
+        let mut offset = (self.orientation * self.target.inverse()).scaled_axis();
+        let spring = self.spring.coefficients(delta_time);
+        for i in 0..3 {
+            (offset[i], self.angular_velocity[i]) =
+                spring.update(offset[i], self.angular_velocity[i], 0.0);
+        }
+        self.orientation = UnitQuaternion::from_scaled_axis(offset) * self.target;
+    }
+
+    /// Sets the orientation the spring turns towards.
+    pub fn set_target(&mut self, target: UnitQuaternion<Real>) {
+        self.target = target;
+    }
+
+    /// Moves the spring immediately, keeping its angular velocity.
+    pub fn set_orientation(&mut self, orientation: UnitQuaternion<Real>) {
+        self.orientation = orientation;
+    }
+
+    pub fn set_angular_velocity(&mut self, angular_velocity: Vector3<Real>) {
+        self.angular_velocity = angular_velocity;
+    }
+
+    pub fn orientation(&self) -> UnitQuaternion<Real> {
+        self.orientation
+    }
+
+    pub fn angular_velocity(&self) -> Vector3<Real> {
+        self.angular_velocity
+    }
+
+    pub fn target(&self) -> UnitQuaternion<Real> {
+        self.target
+    }
+
+    /// Returns `true` if the spring is within `tolerance` radians of its
+    /// target and turning slower than `tolerance`.
+    pub fn is_at_rest(&self, tolerance: Real) -> bool {
+        self.orientation.angle_to(&self.target) <= tolerance
+            && self.angular_velocity.norm() <= tolerance
+    }
 }